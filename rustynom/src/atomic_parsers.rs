@@ -1,4 +1,6 @@
-use crate::{ParseResult, ParsingPosition, parsable::Parsable, parser::RawTestParser};
+use crate::{
+    ParseResult, ParsingPosition, context::ParseContext, parsable::Parsable, parser::RawTestParser,
+};
 
 // ---------------
 // String parser
@@ -19,10 +21,17 @@ impl<TIn: Parsable> LiteralListParser<TIn> {
     }
 }
 
-impl<TIn: Parsable, const ERROR: bool> RawTestParser<TIn, ERROR> for LiteralListParser<TIn> {
+impl<'a, TIn: Parsable, const ERROR: bool> RawTestParser<'a, TIn, ERROR>
+    for LiteralListParser<TIn>
+{
     type TOut = TIn::List;
 
-    fn parse(&self, input: &[TIn::T], position: &mut ParsingPosition) -> ParseResult<TIn::List> {
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<TIn::List> {
         let error = if ERROR {
             Some(vec![TIn::list_to_string(&self.list)])
         } else {
@@ -31,7 +40,9 @@ impl<TIn: Parsable, const ERROR: bool> RawTestParser<TIn, ERROR> for LiteralList
 
         match position.current_eq_slice(input, &self.slice) {
             true => position.succeed_offset(self.slice.len(), self.list.clone()),
-            false => position.fail_offset(0, error),
+            false => context
+                .incomplete_literal(input, position, &self.slice)
+                .unwrap_or_else(|| position.fail_offset(0, error)),
         }
     }
 }
@@ -51,12 +62,17 @@ impl<TIn: Parsable> LiteralListParserNoOutput<TIn> {
     }
 }
 
-impl<TIn: Parsable, const ERROR: bool> RawTestParser<TIn, ERROR>
+impl<'a, TIn: Parsable, const ERROR: bool> RawTestParser<'a, TIn, ERROR>
     for LiteralListParserNoOutput<TIn>
 {
     type TOut = ();
 
-    fn parse(&self, input: &[TIn::T], position: &mut ParsingPosition) -> ParseResult<()> {
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<()> {
         let error = if ERROR {
             Some(vec![TIn::list_to_string(&self.list)])
         } else {
@@ -65,7 +81,9 @@ impl<TIn: Parsable, const ERROR: bool> RawTestParser<TIn, ERROR>
 
         match position.current_eq_slice(input, &self.slice) {
             true => position.succeed_offset(self.slice.len(), ()),
-            false => position.fail_offset(0, error),
+            false => context
+                .incomplete_literal(input, position, &self.slice)
+                .unwrap_or_else(|| position.fail_offset(0, error)),
         }
     }
 }
@@ -91,12 +109,17 @@ impl<TIn: Parsable, TOut: Clone> LiteralListMapParser<TIn, TOut> {
     }
 }
 
-impl<TIn: Parsable, TOut: Clone, const ERROR: bool> RawTestParser<TIn, ERROR>
+impl<'a, TIn: Parsable, TOut: Clone, const ERROR: bool> RawTestParser<'a, TIn, ERROR>
     for LiteralListMapParser<TIn, TOut>
 {
     type TOut = TOut;
 
-    fn parse(&self, input: &[TIn::T], position: &mut ParsingPosition) -> ParseResult<TOut> {
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<TOut> {
         let error = if ERROR {
             Some(vec![TIn::list_to_string(&self.list)])
         } else {
@@ -105,7 +128,9 @@ impl<TIn: Parsable, TOut: Clone, const ERROR: bool> RawTestParser<TIn, ERROR>
 
         match position.current_eq_slice(input, &self.slice) {
             true => position.succeed_offset(self.slice.len(), self.out.clone()),
-            false => position.fail_offset(0, error),
+            false => context
+                .incomplete_literal(input, position, &self.slice)
+                .unwrap_or_else(|| position.fail_offset(0, error)),
         }
     }
 }
@@ -125,10 +150,15 @@ impl<TIn: Parsable> LiteralParser<TIn> {
     }
 }
 
-impl<TIn: Parsable, const ERROR: bool> RawTestParser<TIn, ERROR> for LiteralParser<TIn> {
+impl<'a, TIn: Parsable, const ERROR: bool> RawTestParser<'a, TIn, ERROR> for LiteralParser<TIn> {
     type TOut = TIn::T;
 
-    fn parse(&self, input: &[TIn::T], position: &mut ParsingPosition) -> ParseResult<TIn::T> {
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<TIn::T> {
         let error = if ERROR {
             Some(vec![TIn::t_to_string(&self.literal)])
         } else {
@@ -136,7 +166,9 @@ impl<TIn: Parsable, const ERROR: bool> RawTestParser<TIn, ERROR> for LiteralPars
         };
 
         if position.at_eof(input) {
-            return position.fail_offset(0, error);
+            return context
+                .incomplete_at(input, position.index(), 1)
+                .unwrap_or_else(|| position.fail_offset(0, error));
         }
 
         if position.current_eq(input, &self.literal) {
@@ -158,10 +190,17 @@ impl<TIn: Parsable> LiteralParserNoOutput<TIn> {
     }
 }
 
-impl<TIn: Parsable, const ERROR: bool> RawTestParser<TIn, ERROR> for LiteralParserNoOutput<TIn> {
+impl<'a, TIn: Parsable, const ERROR: bool> RawTestParser<'a, TIn, ERROR>
+    for LiteralParserNoOutput<TIn>
+{
     type TOut = ();
 
-    fn parse(&self, input: &[TIn::T], position: &mut ParsingPosition) -> ParseResult<()> {
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<()> {
         let error = if ERROR {
             Some(vec![TIn::t_to_string(&self.literal)])
         } else {
@@ -169,7 +208,9 @@ impl<TIn: Parsable, const ERROR: bool> RawTestParser<TIn, ERROR> for LiteralPars
         };
 
         if position.at_eof(input) {
-            return position.fail_offset(0, error);
+            return context
+                .incomplete_at(input, position.index(), 1)
+                .unwrap_or_else(|| position.fail_offset(0, error));
         }
 
         if position.current_eq(input, &self.literal) {
@@ -193,11 +234,18 @@ impl EofParser {
     }
 }
 
-impl<TIn: Parsable, const ERROR: bool> RawTestParser<TIn, ERROR> for EofParser {
+impl<'a, TIn: Parsable, const ERROR: bool> RawTestParser<'a, TIn, ERROR> for EofParser {
     type TOut = ();
 
-    fn parse(&self, input: &[TIn::T], position: &mut ParsingPosition) -> ParseResult<()> {
-        if position.at_eof(input) {
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<()> {
+        if let Some(incomplete) = context.incomplete_at(input, position.index(), 1) {
+            incomplete
+        } else if position.at_eof(input) {
             position.succeed_offset(0, ())
         } else {
             let error = if ERROR {
@@ -225,12 +273,17 @@ impl<TOut: Clone> SuccessParser<TOut> {
     }
 }
 
-impl<TIn: Parsable, TOut: Clone, const ERROR: bool> RawTestParser<TIn, ERROR>
+impl<'a, TIn: Parsable, TOut: Clone, const ERROR: bool> RawTestParser<'a, TIn, ERROR>
     for SuccessParser<TOut>
 {
     type TOut = TOut;
 
-    fn parse(&self, _input: &[TIn::T], position: &mut ParsingPosition) -> ParseResult<TOut> {
+    fn parse(
+        &self,
+        _input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        _context: &mut ParseContext<'a>,
+    ) -> ParseResult<TOut> {
         position.succeed_offset(0, self.result.clone())
     }
 }
@@ -243,15 +296,19 @@ impl<TIn: Parsable, TOut: Clone, const ERROR: bool> RawTestParser<TIn, ERROR>
 pub struct CustomParser<
     TIn: Parsable,
     TOut: Clone,
-    TFn: Fn(&[TIn::T], &mut ParsingPosition) -> ParseResult<TOut>,
+    TFn: Fn(&[TIn::T], &mut ParsingPosition, &mut ParseContext) -> ParseResult<TOut>,
 > {
     f: TFn,
     __phantom1: std::marker::PhantomData<TIn>,
     __phantom2: std::marker::PhantomData<TOut>,
 }
 
-impl<'b, TIn: Parsable, TOut: Clone, TFn: Fn(&[TIn::T], &mut ParsingPosition) -> ParseResult<TOut>>
-    CustomParser<TIn, TOut, TFn>
+impl<
+    'b,
+    TIn: Parsable,
+    TOut: Clone,
+    TFn: Fn(&[TIn::T], &mut ParsingPosition, &mut ParseContext) -> ParseResult<TOut>,
+> CustomParser<TIn, TOut, TFn>
 {
     pub fn new(f: TFn) -> Self {
         CustomParser {
@@ -266,13 +323,18 @@ impl<
     'a,
     TIn: Parsable,
     TOut: Clone,
-    TFn: Fn(&[TIn::T], &mut ParsingPosition) -> ParseResult<TOut>,
+    TFn: Fn(&[TIn::T], &mut ParsingPosition, &mut ParseContext) -> ParseResult<TOut>,
     const ERROR: bool,
-> RawTestParser<TIn, ERROR> for CustomParser<TIn, TOut, TFn>
+> RawTestParser<'a, TIn, ERROR> for CustomParser<TIn, TOut, TFn>
 {
     type TOut = TOut;
 
-    fn parse(&self, input: &[TIn::T], position: &mut ParsingPosition) -> ParseResult<TOut> {
-        (self.f)(input, position)
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<TOut> {
+        (self.f)(input, position, context)
     }
 }
@@ -0,0 +1,430 @@
+use crate::{
+    ParseFailure, ParseResult, ParsingPosition, ParsingRange,
+    context::ParseContext,
+    parsable::Parsable,
+    parser::{ParserOut, RawTestParser},
+};
+
+// ---------------
+// Number parsers
+// ---------------
+
+pub trait BinaryNumber: Sized + Clone {
+    const SIZE: usize;
+    const NAME: &'static str;
+
+    fn from_be_slice(bytes: &[u8]) -> Self;
+    fn from_le_slice(bytes: &[u8]) -> Self;
+}
+
+macro_rules! impl_binary_number {
+    ($($t:ident),*) => {
+        $(
+            impl BinaryNumber for $t {
+                const SIZE: usize = std::mem::size_of::<$t>();
+                const NAME: &'static str = stringify!($t);
+
+                fn from_be_slice(bytes: &[u8]) -> Self {
+                    $t::from_be_bytes(bytes.try_into().unwrap())
+                }
+
+                fn from_le_slice(bytes: &[u8]) -> Self {
+                    $t::from_le_bytes(bytes.try_into().unwrap())
+                }
+            }
+        )*
+    };
+}
+
+impl_binary_number!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+#[derive(Clone)]
+pub struct BinaryNumberParser<TIn: Parsable, N: BinaryNumber, const BIG_ENDIAN: bool> {
+    __phantom1: std::marker::PhantomData<TIn>,
+    __phantom2: std::marker::PhantomData<N>,
+}
+
+impl<TIn: Parsable, N: BinaryNumber, const BIG_ENDIAN: bool>
+    BinaryNumberParser<TIn, N, BIG_ENDIAN>
+{
+    pub fn new() -> Self {
+        BinaryNumberParser {
+            __phantom1: std::marker::PhantomData,
+            __phantom2: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<TIn: Parsable, N: BinaryNumber, const BIG_ENDIAN: bool> Default
+    for BinaryNumberParser<TIn, N, BIG_ENDIAN>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, TIn: Parsable<T = u8>, N: BinaryNumber, const BIG_ENDIAN: bool, const ERROR: bool>
+    RawTestParser<'a, TIn, ERROR> for BinaryNumberParser<TIn, N, BIG_ENDIAN>
+{
+    type TOut = N;
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<N> {
+        match position.slice(input).get(..N::SIZE) {
+            Some(bytes) if BIG_ENDIAN => position.succeed_offset(N::SIZE, N::from_be_slice(bytes)),
+            Some(bytes) => position.succeed_offset(N::SIZE, N::from_le_slice(bytes)),
+            None => {
+                let missing = N::SIZE - position.slice(input).len();
+                if let Some(incomplete) = context.incomplete_at(input, input.len(), missing) {
+                    return incomplete;
+                }
+
+                let error = if ERROR {
+                    let endianness = if BIG_ENDIAN {
+                        "big-endian"
+                    } else {
+                        "little-endian"
+                    };
+                    Some(vec![format!("a {} {}", endianness, N::NAME)])
+                } else {
+                    None
+                };
+                position.fail_offset(0, error)
+            }
+        }
+    }
+}
+
+macro_rules! binary_number_fns {
+    ($($be:ident, $le:ident: $t:ident),*) => {
+        $(
+            pub fn $be<TIn: Parsable<T = u8>>() -> BinaryNumberParser<TIn, $t, true> {
+                BinaryNumberParser::new()
+            }
+
+            pub fn $le<TIn: Parsable<T = u8>>() -> BinaryNumberParser<TIn, $t, false> {
+                BinaryNumberParser::new()
+            }
+        )*
+    };
+}
+
+binary_number_fns!(
+    be_u8, le_u8: u8,
+    be_u16, le_u16: u16,
+    be_u32, le_u32: u32,
+    be_u64, le_u64: u64,
+    be_u128, le_u128: u128,
+    be_i8, le_i8: i8,
+    be_i16, le_i16: i16,
+    be_i32, le_i32: i32,
+    be_i64, le_i64: i64,
+    be_i128, le_i128: i128,
+    be_f32, le_f32: f32,
+    be_f64, le_f64: f64
+);
+
+// ---------------
+// Take parser
+// ---------------
+
+#[derive(Clone)]
+pub struct TakeParser {
+    count: usize,
+}
+
+impl<'a, TIn: Parsable, const ERROR: bool> RawTestParser<'a, TIn, ERROR> for TakeParser
+where
+    TIn::T: 'a,
+{
+    type TOut = &'a [TIn::T];
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<&'a [TIn::T]> {
+        match position.slice(input).get(..self.count) {
+            Some(slice) => position.succeed_offset(self.count, slice),
+            None => {
+                let missing = self.count - position.slice(input).len();
+                if let Some(incomplete) = context.incomplete_at(input, input.len(), missing) {
+                    return incomplete;
+                }
+
+                let error = if ERROR {
+                    Some(vec![format!("{} items", self.count)])
+                } else {
+                    None
+                };
+                position.fail_offset(0, error)
+            }
+        }
+    }
+}
+
+/// Takes exactly `count` items from the input.
+pub fn take(count: usize) -> TakeParser {
+    TakeParser { count }
+}
+
+// ---------------
+// Tag parser
+// ---------------
+
+#[derive(Clone)]
+pub struct TagParser<TIn: Parsable> {
+    tag: Box<[TIn::T]>,
+}
+
+impl<'a, TIn: Parsable, const ERROR: bool> RawTestParser<'a, TIn, ERROR> for TagParser<TIn>
+where
+    TIn::T: 'a,
+{
+    type TOut = &'a [TIn::T];
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<&'a [TIn::T]> {
+        if position.current_eq_slice(input, &self.tag) {
+            let slice = position.slice_with_length(input, self.tag.len());
+            position.succeed_offset(self.tag.len(), slice)
+        } else if let Some(incomplete) = context.incomplete_literal(input, position, &self.tag) {
+            incomplete
+        } else {
+            let error = if ERROR {
+                Some(vec![TIn::list_to_string(&TIn::slice_to_list(&self.tag))])
+            } else {
+                None
+            };
+            position.fail_offset(0, error)
+        }
+    }
+}
+
+pub fn tag<TIn: Parsable>(tag: &[TIn::T]) -> TagParser<TIn> {
+    TagParser {
+        tag: Box::from(tag),
+    }
+}
+
+// ---------------
+// Length prefixed parser
+// ---------------
+
+#[derive(Clone)]
+pub struct LengthPrefixedParser<TIn: Parsable, PL, P, const ERROR: bool> {
+    length: PL,
+    parser: P,
+    __phantom1: std::marker::PhantomData<TIn>,
+}
+
+impl<TIn: Parsable, PL, P, const ERROR: bool> LengthPrefixedParser<TIn, PL, P, ERROR> {
+    pub fn new(length: PL, parser: P) -> Self {
+        LengthPrefixedParser {
+            length,
+            parser,
+            __phantom1: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<
+    'a,
+    TIn: Parsable,
+    PL: RawTestParser<'a, TIn, ERROR>,
+    P: RawTestParser<'a, TIn, ERROR>,
+    const ERROR: bool,
+> RawTestParser<'a, TIn, ERROR> for LengthPrefixedParser<TIn, PL, P, ERROR>
+where
+    ParserOut<'a, PL, TIn, ERROR>: TryInto<usize>,
+{
+    type TOut = ParserOut<'a, P, TIn, ERROR>;
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<Self::TOut> {
+        let length = match self.length.parse(input, position, context) {
+            ParseResult::Success(length) => length,
+            ParseResult::Failure(f) => return ParseResult::Failure(f),
+        };
+
+        let start = position.index();
+        let end = length
+            .try_into()
+            .ok()
+            .and_then(|length| start.checked_add(length));
+        // a block that ends exactly at the end of the buffer is complete
+        if let Some(end) = end
+            && end > input.len()
+            && let Some(incomplete) = context.incomplete_at(input, end, end - input.len())
+        {
+            return incomplete;
+        }
+        let Some(end) = end.filter(|end| *end <= input.len()) else {
+            let error = if ERROR {
+                Some(vec!["more input".to_string()])
+            } else {
+                None
+            };
+            return position.fail_at(input.len().into(), error);
+        };
+
+        // the inner parser only sees the block, anything it leaves unconsumed is skipped; the
+        // block is complete, so the end of it is not the end of the buffer
+        let partial = context.is_partial();
+        context.set_partial(false);
+        let result = self.parser.parse(&input[..end], position, context);
+        context.set_partial(partial);
+
+        match result {
+            ParseResult::Success(t) => position.succeed_at(end.into(), t),
+            ParseResult::Failure(f) => ParseResult::Failure(f),
+        }
+    }
+}
+
+/// Parses a length with `length`, then runs `parser` on exactly that many of the following items.
+pub fn length_prefixed<TIn: Parsable, PL, P, const ERROR: bool>(
+    length: PL,
+    parser: P,
+) -> LengthPrefixedParser<TIn, PL, P, ERROR> {
+    LengthPrefixedParser::new(length, parser)
+}
+
+// ---------------
+// Varint parser
+// ---------------
+
+#[derive(Clone)]
+pub struct VarintParser;
+
+impl<'a, TIn: Parsable<T = u8>, const ERROR: bool> RawTestParser<'a, TIn, ERROR> for VarintParser {
+    type TOut = u64;
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<u64> {
+        let mut value = 0_u64;
+        for (i, byte) in position.slice(input).iter().take(10).enumerate() {
+            // the 10th byte only holds the highest bit
+            if i == 9 && *byte > 1 {
+                if ERROR {
+                    let span = ParsingRange::new(position.clone(), (position.index() + 10).into());
+                    return ParseResult::Failure(ParseFailure::with_error(
+                        span,
+                        "varint too large".to_string(),
+                    ));
+                }
+                return position.fail_offset(0, None);
+            }
+
+            value |= u64::from(byte & 0x7f) << (7 * i);
+            if byte & 0x80 == 0 {
+                return position.succeed_offset(i + 1, value);
+            }
+        }
+
+        if position.slice(input).len() < 10
+            && let Some(incomplete) = context.incomplete_at(input, input.len(), 1)
+        {
+            return incomplete;
+        }
+
+        let error = if ERROR {
+            Some(vec!["a varint".to_string()])
+        } else {
+            None
+        };
+        position.fail_offset(0, error)
+    }
+}
+
+/// Parses an unsigned LEB128 varint, as used by protobuf.
+pub fn varint() -> VarintParser {
+    VarintParser
+}
+
+// ---------------
+// Bit fields parser
+// ---------------
+
+#[derive(Clone)]
+pub struct BitFieldsParser<const N: usize> {
+    widths: [u32; N],
+    bytes: usize,
+}
+
+impl<'a, TIn: Parsable<T = u8>, const N: usize, const ERROR: bool> RawTestParser<'a, TIn, ERROR>
+    for BitFieldsParser<N>
+{
+    type TOut = [u64; N];
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<[u64; N]> {
+        let Some(bytes) = position.slice(input).get(..self.bytes) else {
+            let missing = self.bytes - position.slice(input).len();
+            if let Some(incomplete) = context.incomplete_at(input, input.len(), missing) {
+                return incomplete;
+            }
+
+            let error = if ERROR {
+                Some(vec![format!("{} bytes of bit fields", self.bytes)])
+            } else {
+                None
+            };
+            return position.fail_offset(0, error);
+        };
+
+        let bits = bytes
+            .iter()
+            .fold(0_u128, |acc, byte| (acc << 8) | u128::from(*byte));
+        let mut remaining = self.bytes as u32 * 8;
+        let fields = self.widths.map(|width| {
+            remaining -= width;
+            let mask = (1_u128 << width) - 1;
+            ((bits >> remaining) & mask) as u64
+        });
+
+        position.succeed_offset(self.bytes, fields)
+    }
+}
+
+/// Splits the next bytes into fields of the given bit widths, most significant bit first.
+///
+/// The widths have to add up to whole bytes, at most 16 of them, and each field can be at most
+/// 64 bits wide.
+pub fn bit_fields<const N: usize>(widths: [u32; N]) -> BitFieldsParser<N> {
+    let total: u32 = widths.iter().sum();
+    assert!(
+        total.is_multiple_of(8) && total <= 128,
+        "bit fields must fill between 0 and 16 whole bytes"
+    );
+    assert!(
+        widths.iter().all(|width| *width <= 64),
+        "bit fields can be at most 64 bits wide"
+    );
+
+    BitFieldsParser {
+        widths,
+        bytes: (total / 8) as usize,
+    }
+}
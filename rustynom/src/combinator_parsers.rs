@@ -1,6 +1,9 @@
+use std::sync::Arc;
+
 use crate::{
-    GenericParseResult, Parsable, ParseResult, ParsingPosition,
-    parser::{ParserOut, RawTestParser},
+    GenericParseResult, Parsable, ParseFailure, ParseResult, ParsingPosition,
+    context::ParseContext,
+    parser::{IntoBoxed, Local, ParserOut, RawTestParser, Shared, Sharing},
 };
 use rustynom_macros::{and_parser, or_parser};
 
@@ -33,20 +36,13 @@ or_parser!(8);
 // ---------------
 
 #[derive(Clone)]
-pub struct SkipParser<
-    TIn: Parsable,
-    P1: RawTestParser<TIn, ERROR>,
-    P2: RawTestParser<TIn, ERROR>,
-    const ERROR: bool,
-> {
+pub struct SkipParser<TIn: Parsable, P1, P2, const ERROR: bool> {
     parser1: P1,
     parser2: P2,
     __phantom1: std::marker::PhantomData<TIn>,
 }
 
-impl<TIn: Parsable, P1: RawTestParser<TIn, ERROR>, P2: RawTestParser<TIn, ERROR>, const ERROR: bool>
-    SkipParser<TIn, P1, P2, ERROR>
-{
+impl<TIn: Parsable, P1, P2, const ERROR: bool> SkipParser<TIn, P1, P2, ERROR> {
     pub fn new(parser1: P1, parser2: P2) -> Self {
         SkipParser {
             parser1,
@@ -56,18 +52,28 @@ impl<TIn: Parsable, P1: RawTestParser<TIn, ERROR>, P2: RawTestParser<TIn, ERROR>
     }
 }
 
-impl<TIn: Parsable, P1: RawTestParser<TIn, ERROR>, P2: RawTestParser<TIn, ERROR>, const ERROR: bool>
-    RawTestParser<TIn, ERROR> for SkipParser<TIn, P1, P2, ERROR>
+impl<
+    'a,
+    TIn: Parsable,
+    P1: RawTestParser<'a, TIn, ERROR>,
+    P2: RawTestParser<'a, TIn, ERROR>,
+    const ERROR: bool,
+> RawTestParser<'a, TIn, ERROR> for SkipParser<TIn, P1, P2, ERROR>
 {
-    type TOut = ParserOut<P1, TIn, ERROR>;
-
-    fn parse(&self, input: &[TIn::T], position: &mut ParsingPosition) -> ParseResult<Self::TOut> {
-        let result1 = self.parser1.parse(input, position);
+    type TOut = ParserOut<'a, P1, TIn, ERROR>;
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<Self::TOut> {
+        let result1 = self.parser1.parse(input, position, context);
         if let GenericParseResult::Failure(f) = result1 {
             return GenericParseResult::Failure(f);
         }
 
-        let result2 = self.parser2.parse(input, position);
+        let result2 = self.parser2.parse(input, position, context);
         if let GenericParseResult::Failure(f) = result2 {
             return GenericParseResult::Failure(f);
         }
@@ -77,20 +83,13 @@ impl<TIn: Parsable, P1: RawTestParser<TIn, ERROR>, P2: RawTestParser<TIn, ERROR>
 }
 
 #[derive(Clone)]
-pub struct ThenParser<
-    TIn: Parsable,
-    P1: RawTestParser<TIn, ERROR>,
-    P2: RawTestParser<TIn, ERROR>,
-    const ERROR: bool,
-> {
+pub struct ThenParser<TIn: Parsable, P1, P2, const ERROR: bool> {
     parser1: P1,
     parser2: P2,
     __phantom1: std::marker::PhantomData<TIn>,
 }
 
-impl<TIn: Parsable, P1: RawTestParser<TIn, ERROR>, P2: RawTestParser<TIn, ERROR>, const ERROR: bool>
-    ThenParser<TIn, P1, P2, ERROR>
-{
+impl<TIn: Parsable, P1, P2, const ERROR: bool> ThenParser<TIn, P1, P2, ERROR> {
     pub fn new(parser1: P1, parser2: P2) -> Self {
         ThenParser {
             parser1,
@@ -100,18 +99,28 @@ impl<TIn: Parsable, P1: RawTestParser<TIn, ERROR>, P2: RawTestParser<TIn, ERROR>
     }
 }
 
-impl<TIn: Parsable, P1: RawTestParser<TIn, ERROR>, P2: RawTestParser<TIn, ERROR>, const ERROR: bool>
-    RawTestParser<TIn, ERROR> for ThenParser<TIn, P1, P2, ERROR>
+impl<
+    'a,
+    TIn: Parsable,
+    P1: RawTestParser<'a, TIn, ERROR>,
+    P2: RawTestParser<'a, TIn, ERROR>,
+    const ERROR: bool,
+> RawTestParser<'a, TIn, ERROR> for ThenParser<TIn, P1, P2, ERROR>
 {
-    type TOut = ParserOut<P2, TIn, ERROR>;
-
-    fn parse(&self, input: &[TIn::T], position: &mut ParsingPosition) -> ParseResult<Self::TOut> {
-        let result1 = self.parser1.parse(input, position);
+    type TOut = ParserOut<'a, P2, TIn, ERROR>;
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<Self::TOut> {
+        let result1 = self.parser1.parse(input, position, context);
         if let GenericParseResult::Failure(f) = result1 {
             return GenericParseResult::Failure(f);
         }
 
-        let result2 = self.parser2.parse(input, position);
+        let result2 = self.parser2.parse(input, position, context);
         if let GenericParseResult::Failure(f) = result2 {
             return GenericParseResult::Failure(f);
         }
@@ -124,65 +133,201 @@ impl<TIn: Parsable, P1: RawTestParser<TIn, ERROR>, P2: RawTestParser<TIn, ERROR>
 // Separated by Parser
 // ---------------
 
+/// What a separated list does with a separator after its last element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Trailing {
+    /// A trailing separator is not consumed, the list ends before it and the separator is left
+    /// for the parser that follows.
+    #[default]
+    Leave,
+    /// A separator has to be followed by an element, `[1,2,]` fails after the last comma.
+    Forbid,
+    /// A trailing separator is consumed if present.
+    Allow,
+    /// Every element has to be followed by a separator, as in `a; b;`.
+    Require,
+}
+
 #[derive(Clone)]
-pub struct SeparatedByParser<
-    TIn: Parsable,
-    P1: RawTestParser<TIn, ERROR>,
-    P2: RawTestParser<TIn, ERROR>,
-    const ERROR: bool,
-> {
+pub struct SeparatedByParser<TIn: Parsable, P1, P2, const ERROR: bool> {
     parser: P1,
     separator: P2,
+    allow_empty: bool,
+    trailing: Trailing,
     __phantom1: std::marker::PhantomData<TIn>,
 }
 
-impl<TIn: Parsable, P1: RawTestParser<TIn, ERROR>, P2: RawTestParser<TIn, ERROR>, const ERROR: bool>
-    SeparatedByParser<TIn, P1, P2, ERROR>
-{
+impl<TIn: Parsable, P1, P2, const ERROR: bool> SeparatedByParser<TIn, P1, P2, ERROR> {
     pub fn new(parser: P1, separator: P2) -> Self {
         SeparatedByParser {
             parser,
             separator,
+            allow_empty: false,
+            trailing: Trailing::default(),
             __phantom1: std::marker::PhantomData,
         }
     }
+
+    /// Succeeds with an empty list instead of failing when there is no first element.
+    pub fn allow_empty(mut self) -> Self {
+        self.allow_empty = true;
+        self
+    }
+
+    pub fn trailing(mut self, trailing: Trailing) -> Self {
+        self.trailing = trailing;
+        self
+    }
+
+    /// Returns the separators alongside the elements.
+    pub fn keep_separators(self) -> SeparatedByKeepParser<TIn, P1, P2, ERROR> {
+        SeparatedByKeepParser { parser: self }
+    }
 }
 
-impl<TIn: Parsable, P1: RawTestParser<TIn, ERROR>, P2: RawTestParser<TIn, ERROR>, const ERROR: bool>
-    RawTestParser<TIn, ERROR> for SeparatedByParser<TIn, P1, P2, ERROR>
+impl<'a, TIn: Parsable, P1: RawTestParser<'a, TIn, ERROR>, P2, const ERROR: bool>
+    SeparatedByParser<TIn, P1, P2, ERROR>
 {
-    type TOut = Vec<ParserOut<P1, TIn, ERROR>>;
-
-    fn parse(&self, input: &[TIn::T], position: &mut ParsingPosition) -> ParseResult<Self::TOut> {
+    fn parse_list<S>(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+        mut on_separator: impl FnMut(S),
+    ) -> ParseResult<Vec<ParserOut<'a, P1, TIn, ERROR>>>
+    where
+        P2: RawTestParser<'a, TIn, ERROR, TOut = S>,
+    {
         let mut result = Vec::new();
 
-        let first = self.parser.parse(input, position);
-
-        if first.is_failure() {
-            return ParseResult::Failure(first.unwrap_failure());
+        let cloned_position = position.clone();
+        let diagnostics = context.diagnostics_len();
+        match self.parser.parse(input, position, context) {
+            ParseResult::Success(t) => result.push(t),
+            ParseResult::Failure(f) if self.allow_empty && !f.committed => {
+                position.advance_to(cloned_position);
+                context.truncate_diagnostics(diagnostics);
+                return ParseResult::Success(result);
+            }
+            ParseResult::Failure(f) => return ParseResult::Failure(f),
         }
 
-        result.push(first.unwrap_success());
-
-        let mut cloned_position = position.clone();
         loop {
-            let separator_result = self.separator.parse(input, position);
-            if separator_result.is_failure() {
-                // if the separator fails, the sequence is over and we return the result
-                // we don't need to move the position back because the separator failed
-                return ParseResult::Success(result);
+            let cloned_position = position.clone();
+            let diagnostics = context.diagnostics_len();
+            let separator = match self.separator.parse(input, position, context) {
+                ParseResult::Success(s) => s,
+                ParseResult::Failure(f) if f.committed || self.trailing == Trailing::Require => {
+                    return ParseResult::Failure(f);
+                }
+                ParseResult::Failure(_) => {
+                    // the list ends with the last element
+                    position.advance_to(cloned_position);
+                    context.truncate_diagnostics(diagnostics);
+                    return ParseResult::Success(result);
+                }
+            };
+
+            let separator_position = position.clone();
+            let separator_diagnostics = context.diagnostics_len();
+            match self.parser.parse(input, position, context) {
+                ParseResult::Success(_) if *position == cloned_position => {
+                    return ParseResult::Failure(ParseFailure::no_progress::<P1>(cloned_position));
+                }
+                ParseResult::Success(t) => {
+                    on_separator(separator);
+                    result.push(t);
+                }
+                ParseResult::Failure(f) if f.committed || self.trailing == Trailing::Forbid => {
+                    return ParseResult::Failure(f);
+                }
+                ParseResult::Failure(_) if self.trailing == Trailing::Leave => {
+                    // the list ends with the last element, before the separator
+                    position.advance_to(cloned_position);
+                    context.truncate_diagnostics(diagnostics);
+                    return ParseResult::Success(result);
+                }
+                ParseResult::Failure(_) => {
+                    // the list ends with a trailing separator
+                    on_separator(separator);
+                    position.advance_to(separator_position);
+                    context.truncate_diagnostics(separator_diagnostics);
+                    return ParseResult::Success(result);
+                }
             }
+        }
+    }
+}
 
-            let self_result = self.parser.parse(input, position);
-            if self_result.is_failure() {
-                // if the self parser fails, we return the result
-                // we need to move the position back to the last successful position
-                position.advance_to(cloned_position);
-                return ParseResult::Success(result);
-            }
+impl<
+    'a,
+    TIn: Parsable,
+    P1: RawTestParser<'a, TIn, ERROR>,
+    P2: RawTestParser<'a, TIn, ERROR>,
+    const ERROR: bool,
+> RawTestParser<'a, TIn, ERROR> for SeparatedByParser<TIn, P1, P2, ERROR>
+{
+    type TOut = Vec<ParserOut<'a, P1, TIn, ERROR>>;
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<Self::TOut> {
+        self.parse_list(input, position, context, |_| ())
+    }
+}
+
+#[derive(Clone)]
+pub struct SeparatedByKeepParser<TIn: Parsable, P1, P2, const ERROR: bool> {
+    parser: SeparatedByParser<TIn, P1, P2, ERROR>,
+}
+
+impl<TIn: Parsable, P1, P2, const ERROR: bool> SeparatedByKeepParser<TIn, P1, P2, ERROR> {
+    pub fn new(parser: P1, separator: P2) -> Self {
+        SeparatedByParser::new(parser, separator).keep_separators()
+    }
 
-            result.push(self_result.unwrap_success());
-            cloned_position = position.clone();
+    pub fn allow_empty(self) -> Self {
+        SeparatedByKeepParser {
+            parser: self.parser.allow_empty(),
+        }
+    }
+
+    pub fn trailing(self, trailing: Trailing) -> Self {
+        SeparatedByKeepParser {
+            parser: self.parser.trailing(trailing),
+        }
+    }
+}
+
+impl<
+    'a,
+    TIn: Parsable,
+    P1: RawTestParser<'a, TIn, ERROR>,
+    P2: RawTestParser<'a, TIn, ERROR>,
+    const ERROR: bool,
+> RawTestParser<'a, TIn, ERROR> for SeparatedByKeepParser<TIn, P1, P2, ERROR>
+{
+    type TOut = (
+        Vec<ParserOut<'a, P1, TIn, ERROR>>,
+        Vec<ParserOut<'a, P2, TIn, ERROR>>,
+    );
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<Self::TOut> {
+        let mut separators = Vec::new();
+        match self
+            .parser
+            .parse_list(input, position, context, |s| separators.push(s))
+        {
+            ParseResult::Success(items) => ParseResult::Success((items, separators)),
+            ParseResult::Failure(f) => ParseResult::Failure(f),
         }
     }
 }
@@ -192,27 +337,14 @@ impl<TIn: Parsable, P1: RawTestParser<TIn, ERROR>, P2: RawTestParser<TIn, ERROR>
 // ---------------
 
 #[derive(Clone)]
-pub struct SurroundParser<
-    TIn: Parsable,
-    P: RawTestParser<TIn, ERROR>,
-    PL: RawTestParser<TIn, ERROR>,
-    PR: RawTestParser<TIn, ERROR>,
-    const ERROR: bool,
-> {
+pub struct SurroundParser<TIn: Parsable, P, PL, PR, const ERROR: bool> {
     parser: P,
     left: PL,
     right: PR,
     __phantom1: std::marker::PhantomData<TIn>,
 }
 
-impl<
-    TIn: Parsable,
-    P: RawTestParser<TIn, ERROR>,
-    PL: RawTestParser<TIn, ERROR>,
-    PR: RawTestParser<TIn, ERROR>,
-    const ERROR: bool,
-> SurroundParser<TIn, P, PL, PR, ERROR>
-{
+impl<TIn: Parsable, P, PL, PR, const ERROR: bool> SurroundParser<TIn, P, PL, PR, ERROR> {
     pub fn new(parser: P, left: PL, right: PR) -> Self {
         SurroundParser {
             parser,
@@ -224,27 +356,33 @@ impl<
 }
 
 impl<
+    'a,
     TIn: Parsable,
-    P: RawTestParser<TIn, ERROR>,
-    PL: RawTestParser<TIn, ERROR>,
-    PR: RawTestParser<TIn, ERROR>,
+    P: RawTestParser<'a, TIn, ERROR>,
+    PL: RawTestParser<'a, TIn, ERROR>,
+    PR: RawTestParser<'a, TIn, ERROR>,
     const ERROR: bool,
-> RawTestParser<TIn, ERROR> for SurroundParser<TIn, P, PL, PR, ERROR>
+> RawTestParser<'a, TIn, ERROR> for SurroundParser<TIn, P, PL, PR, ERROR>
 {
-    type TOut = ParserOut<P, TIn, ERROR>;
-
-    fn parse(&self, input: &[TIn::T], position: &mut ParsingPosition) -> ParseResult<Self::TOut> {
-        let result1 = self.left.parse(input, position);
+    type TOut = ParserOut<'a, P, TIn, ERROR>;
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<Self::TOut> {
+        let result1 = self.left.parse(input, position, context);
         if let GenericParseResult::Failure(f) = result1 {
             return GenericParseResult::Failure(f);
         }
 
-        let result2 = self.parser.parse(input, position);
+        let result2 = self.parser.parse(input, position, context);
         if let GenericParseResult::Failure(f) = result2 {
             return GenericParseResult::Failure(f);
         }
 
-        let result3 = self.right.parse(input, position);
+        let result3 = self.right.parse(input, position, context);
         if let GenericParseResult::Failure(f) = result3 {
             return GenericParseResult::Failure(f);
         }
@@ -252,3 +390,521 @@ impl<
         result2
     }
 }
+
+// ---------------
+// Cut Parser
+// ---------------
+
+#[derive(Clone)]
+pub struct CutParser<TIn: Parsable, P, const ERROR: bool> {
+    parser: P,
+    __phantom1: std::marker::PhantomData<TIn>,
+}
+
+impl<TIn: Parsable, P, const ERROR: bool> CutParser<TIn, P, ERROR> {
+    pub fn new(parser: P) -> Self {
+        CutParser {
+            parser,
+            __phantom1: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<'a, TIn: Parsable, P: RawTestParser<'a, TIn, ERROR>, const ERROR: bool>
+    RawTestParser<'a, TIn, ERROR> for CutParser<TIn, P, ERROR>
+{
+    type TOut = ParserOut<'a, P, TIn, ERROR>;
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<Self::TOut> {
+        match self.parser.parse(input, position, context) {
+            GenericParseResult::Failure(mut f) => {
+                // the enclosing alternatives and repetitions propagate this failure
+                // instead of backtracking and trying something else
+                f.committed = true;
+                GenericParseResult::Failure(f)
+            }
+            result => result,
+        }
+    }
+}
+
+// ---------------
+// Precedence Parser
+// ---------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Associativity {
+    Left,
+    Right,
+}
+
+type OperatorParser<TIn, const ERROR: bool, S> = Arc<<S as Sharing>::Parser<TIn, (), ERROR>>;
+
+struct UnaryOperator<TIn: Parsable, TOut, const ERROR: bool, S: Sharing> {
+    parser: OperatorParser<TIn, ERROR, S>,
+    binding_power: u32,
+    f: Arc<S::Unary<TOut>>,
+}
+
+impl<TIn: Parsable, TOut, const ERROR: bool, S: Sharing> Clone
+    for UnaryOperator<TIn, TOut, ERROR, S>
+{
+    fn clone(&self) -> Self {
+        UnaryOperator {
+            parser: self.parser.clone(),
+            binding_power: self.binding_power,
+            f: self.f.clone(),
+        }
+    }
+}
+
+struct InfixOperator<TIn: Parsable, TOut, const ERROR: bool, S: Sharing> {
+    parser: OperatorParser<TIn, ERROR, S>,
+    binding_power: u32,
+    associativity: Associativity,
+    f: Arc<S::Binary<TOut>>,
+}
+
+impl<TIn: Parsable, TOut, const ERROR: bool, S: Sharing> Clone
+    for InfixOperator<TIn, TOut, ERROR, S>
+{
+    fn clone(&self) -> Self {
+        InfixOperator {
+            parser: self.parser.clone(),
+            binding_power: self.binding_power,
+            associativity: self.associativity,
+            f: self.f.clone(),
+        }
+    }
+}
+
+/// Parses operator expressions over `atom` with precedence climbing. Operators with a higher
+/// binding power bind tighter, a prefix operator binds tighter than infix operators of the same
+/// binding power. Prefix and postfix operators must consume input, one that succeeds without
+/// consuming anything fails the parse.
+#[derive(Clone)]
+pub struct PrecedenceParser<TIn: Parsable, TAtom, TOut, const ERROR: bool, S: Sharing = Local> {
+    atom: TAtom,
+    prefix: Vec<UnaryOperator<TIn, TOut, ERROR, S>>,
+    postfix: Vec<UnaryOperator<TIn, TOut, ERROR, S>>,
+    infix: Vec<InfixOperator<TIn, TOut, ERROR, S>>,
+}
+
+impl<TIn: Parsable, TAtom, TOut, const ERROR: bool> PrecedenceParser<TIn, TAtom, TOut, ERROR> {
+    pub fn new(atom: TAtom) -> Self {
+        PrecedenceParser {
+            atom,
+            prefix: Vec::new(),
+            postfix: Vec::new(),
+            infix: Vec::new(),
+        }
+    }
+}
+
+impl<TIn: Parsable, TAtom, TOut, const ERROR: bool>
+    PrecedenceParser<TIn, TAtom, TOut, ERROR, Shared>
+{
+    /// Only accepts `Send + Sync` operators, see `Sharing`.
+    pub fn new_sync(atom: TAtom) -> Self {
+        PrecedenceParser {
+            atom,
+            prefix: Vec::new(),
+            postfix: Vec::new(),
+            infix: Vec::new(),
+        }
+    }
+}
+
+impl<TIn: Parsable, TAtom, TOut, const ERROR: bool, S: Sharing>
+    PrecedenceParser<TIn, TAtom, TOut, ERROR, S>
+{
+    pub fn prefix(
+        mut self,
+        operator: impl IntoBoxed<S, S::Parser<TIn, (), ERROR>>,
+        binding_power: u32,
+        f: impl IntoBoxed<S, S::Unary<TOut>>,
+    ) -> Self {
+        self.prefix.push(UnaryOperator {
+            parser: Arc::from(operator.into_boxed()),
+            binding_power,
+            f: Arc::from(f.into_boxed()),
+        });
+        self
+    }
+
+    pub fn postfix(
+        mut self,
+        operator: impl IntoBoxed<S, S::Parser<TIn, (), ERROR>>,
+        binding_power: u32,
+        f: impl IntoBoxed<S, S::Unary<TOut>>,
+    ) -> Self {
+        self.postfix.push(UnaryOperator {
+            parser: Arc::from(operator.into_boxed()),
+            binding_power,
+            f: Arc::from(f.into_boxed()),
+        });
+        self
+    }
+
+    pub fn infix(
+        mut self,
+        operator: impl IntoBoxed<S, S::Parser<TIn, (), ERROR>>,
+        binding_power: u32,
+        associativity: Associativity,
+        f: impl IntoBoxed<S, S::Binary<TOut>>,
+    ) -> Self {
+        self.infix.push(InfixOperator {
+            parser: Arc::from(operator.into_boxed()),
+            binding_power,
+            associativity,
+            f: Arc::from(f.into_boxed()),
+        });
+        self
+    }
+}
+
+/// Tries `operator` at the current position, rewinding if it does not match.
+fn parse_operator<'a, TIn: Parsable, const ERROR: bool, S: Sharing>(
+    operator: &OperatorParser<TIn, ERROR, S>,
+    input: &'a [TIn::T],
+    position: &mut ParsingPosition,
+    context: &mut ParseContext<'a>,
+) -> ParseResult<()>
+where
+    S::Parser<TIn, (), ERROR>: RawTestParser<'a, TIn, ERROR, TOut = ()>,
+{
+    let start = position.clone();
+    let diagnostics = context.diagnostics_len();
+    let result = operator.parse(input, position, context);
+    if result.is_failure() && !result.is_committed() {
+        position.advance_to(start);
+        context.truncate_diagnostics(diagnostics);
+    }
+    result
+}
+
+impl<
+    'a,
+    TIn: Parsable,
+    TAtom: RawTestParser<'a, TIn, ERROR, TOut = TOut>,
+    TOut,
+    const ERROR: bool,
+    S: Sharing,
+> PrecedenceParser<TIn, TAtom, TOut, ERROR, S>
+where
+    S::Parser<TIn, (), ERROR>: RawTestParser<'a, TIn, ERROR, TOut = ()>,
+{
+    // binding powers are doubled so that associativity can break ties between equal operators
+    fn parse_expression(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+        min_binding_power: u32,
+    ) -> ParseResult<TOut> {
+        let mut lhs = match self.parse_prefix(input, position, context) {
+            ParseResult::Success(lhs) => lhs,
+            ParseResult::Failure(f) => return ParseResult::Failure(f),
+        };
+
+        'operators: loop {
+            for operator in &self.postfix {
+                if operator.binding_power * 2 < min_binding_power {
+                    continue;
+                }
+                let start = position.clone();
+                match parse_operator::<TIn, ERROR, S>(&operator.parser, input, position, context) {
+                    ParseResult::Success(()) if position.index() == start.index() => {
+                        return ParseResult::Failure(ParseFailure::no_progress::<
+                            S::Parser<TIn, (), ERROR>,
+                        >(start));
+                    }
+                    ParseResult::Success(()) => {
+                        lhs = (operator.f)(lhs);
+                        continue 'operators;
+                    }
+                    ParseResult::Failure(f) if f.committed => return ParseResult::Failure(f),
+                    ParseResult::Failure(_) => {}
+                }
+            }
+
+            for operator in &self.infix {
+                let (left_power, right_power) = match operator.associativity {
+                    Associativity::Left => {
+                        (operator.binding_power * 2, operator.binding_power * 2 + 1)
+                    }
+                    Associativity::Right => {
+                        (operator.binding_power * 2 + 1, operator.binding_power * 2)
+                    }
+                };
+                if left_power < min_binding_power {
+                    continue;
+                }
+                match parse_operator::<TIn, ERROR, S>(&operator.parser, input, position, context) {
+                    ParseResult::Success(()) => {
+                        let rhs = match self.parse_expression(input, position, context, right_power)
+                        {
+                            ParseResult::Success(rhs) => rhs,
+                            ParseResult::Failure(f) => return ParseResult::Failure(f),
+                        };
+                        lhs = (operator.f)(lhs, rhs);
+                        continue 'operators;
+                    }
+                    ParseResult::Failure(f) if f.committed => return ParseResult::Failure(f),
+                    ParseResult::Failure(_) => {}
+                }
+            }
+
+            return ParseResult::Success(lhs);
+        }
+    }
+
+    fn parse_prefix(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<TOut> {
+        let mut failure: Option<ParseFailure> = None;
+        for operator in &self.prefix {
+            let start = position.clone();
+            match parse_operator::<TIn, ERROR, S>(&operator.parser, input, position, context) {
+                ParseResult::Success(()) if position.index() == start.index() => {
+                    return ParseResult::Failure(ParseFailure::no_progress::<
+                        S::Parser<TIn, (), ERROR>,
+                    >(start));
+                }
+                ParseResult::Success(()) => {
+                    // the operand takes infix operators of the same binding power as the prefix
+                    // only to its right, `-a - b` is `(-a) - b`
+                    return match self.parse_expression(
+                        input,
+                        position,
+                        context,
+                        operator.binding_power * 2 + 1,
+                    ) {
+                        ParseResult::Success(operand) => {
+                            ParseResult::Success((operator.f)(operand))
+                        }
+                        ParseResult::Failure(f) => ParseResult::Failure(f),
+                    };
+                }
+                ParseResult::Failure(f) if f.committed => return ParseResult::Failure(f),
+                ParseResult::Failure(f) => {
+                    failure = Some(match failure {
+                        Some(failure) => position.merge_failures(failure, f),
+                        None => f,
+                    });
+                }
+            }
+        }
+
+        match self.atom.parse(input, position, context) {
+            ParseResult::Failure(f) if !f.committed => ParseResult::Failure(match failure {
+                Some(failure) => position.merge_failures(failure, f),
+                None => f,
+            }),
+            result => result,
+        }
+    }
+}
+
+impl<
+    'a,
+    TIn: Parsable,
+    TAtom: RawTestParser<'a, TIn, ERROR, TOut = TOut>,
+    TOut,
+    const ERROR: bool,
+    S: Sharing,
+> RawTestParser<'a, TIn, ERROR> for PrecedenceParser<TIn, TAtom, TOut, ERROR, S>
+where
+    S::Parser<TIn, (), ERROR>: RawTestParser<'a, TIn, ERROR, TOut = ()>,
+{
+    type TOut = TOut;
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<TOut> {
+        self.parse_expression(input, position, context, 0)
+    }
+}
+
+pub fn precedence<TIn: Parsable, TAtom, TOut, const ERROR: bool>(
+    atom: TAtom,
+) -> PrecedenceParser<TIn, TAtom, TOut, ERROR> {
+    PrecedenceParser::new(atom)
+}
+
+// ---------------
+// Chain Parsers
+// ---------------
+
+#[derive(Clone)]
+pub struct ChainLeftParser<TIn: Parsable, P, POp, const ERROR: bool> {
+    parser: P,
+    operator: POp,
+    __phantom1: std::marker::PhantomData<TIn>,
+}
+
+impl<TIn: Parsable, P, POp, const ERROR: bool> ChainLeftParser<TIn, P, POp, ERROR> {
+    pub fn new(parser: P, operator: POp) -> Self {
+        ChainLeftParser {
+            parser,
+            operator,
+            __phantom1: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<
+    'a,
+    TIn: Parsable,
+    P: RawTestParser<'a, TIn, ERROR>,
+    POp: RawTestParser<'a, TIn, ERROR>,
+    const ERROR: bool,
+> RawTestParser<'a, TIn, ERROR> for ChainLeftParser<TIn, P, POp, ERROR>
+where
+    ParserOut<'a, POp, TIn, ERROR>: Fn(
+        ParserOut<'a, P, TIn, ERROR>,
+        ParserOut<'a, P, TIn, ERROR>,
+    ) -> ParserOut<'a, P, TIn, ERROR>,
+{
+    type TOut = ParserOut<'a, P, TIn, ERROR>;
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<Self::TOut> {
+        let mut acc = match self.parser.parse(input, position, context) {
+            ParseResult::Success(t) => t,
+            ParseResult::Failure(f) => return ParseResult::Failure(f),
+        };
+
+        loop {
+            let cloned_position = position.clone();
+            let diagnostics = context.diagnostics_len();
+
+            let operator = match self.operator.parse(input, position, context) {
+                ParseResult::Success(operator) => operator,
+                ParseResult::Failure(f) if f.committed => return ParseResult::Failure(f),
+                ParseResult::Failure(_) => {
+                    position.advance_to(cloned_position);
+                    context.truncate_diagnostics(diagnostics);
+                    break;
+                }
+            };
+
+            match self.parser.parse(input, position, context) {
+                ParseResult::Success(_) if *position == cloned_position => {
+                    return ParseResult::Failure(ParseFailure::no_progress::<P>(cloned_position));
+                }
+                ParseResult::Success(rhs) => acc = operator(acc, rhs),
+                ParseResult::Failure(f) if f.committed => return ParseResult::Failure(f),
+                ParseResult::Failure(_) => {
+                    // a dangling operator is not part of the chain
+                    position.advance_to(cloned_position);
+                    context.truncate_diagnostics(diagnostics);
+                    break;
+                }
+            }
+        }
+
+        ParseResult::Success(acc)
+    }
+}
+
+#[derive(Clone)]
+pub struct ChainRightParser<TIn: Parsable, P, POp, const ERROR: bool> {
+    parser: P,
+    operator: POp,
+    __phantom1: std::marker::PhantomData<TIn>,
+}
+
+impl<TIn: Parsable, P, POp, const ERROR: bool> ChainRightParser<TIn, P, POp, ERROR> {
+    pub fn new(parser: P, operator: POp) -> Self {
+        ChainRightParser {
+            parser,
+            operator,
+            __phantom1: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<
+    'a,
+    TIn: Parsable,
+    P: RawTestParser<'a, TIn, ERROR>,
+    POp: RawTestParser<'a, TIn, ERROR>,
+    const ERROR: bool,
+> RawTestParser<'a, TIn, ERROR> for ChainRightParser<TIn, P, POp, ERROR>
+where
+    ParserOut<'a, POp, TIn, ERROR>: Fn(
+        ParserOut<'a, P, TIn, ERROR>,
+        ParserOut<'a, P, TIn, ERROR>,
+    ) -> ParserOut<'a, P, TIn, ERROR>,
+{
+    type TOut = ParserOut<'a, P, TIn, ERROR>;
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<Self::TOut> {
+        let first = match self.parser.parse(input, position, context) {
+            ParseResult::Success(t) => t,
+            ParseResult::Failure(f) => return ParseResult::Failure(f),
+        };
+
+        let mut operands = vec![first];
+        let mut operators = Vec::new();
+        loop {
+            let cloned_position = position.clone();
+            let diagnostics = context.diagnostics_len();
+
+            let operator = match self.operator.parse(input, position, context) {
+                ParseResult::Success(operator) => operator,
+                ParseResult::Failure(f) if f.committed => return ParseResult::Failure(f),
+                ParseResult::Failure(_) => {
+                    position.advance_to(cloned_position);
+                    context.truncate_diagnostics(diagnostics);
+                    break;
+                }
+            };
+
+            match self.parser.parse(input, position, context) {
+                ParseResult::Success(_) if *position == cloned_position => {
+                    return ParseResult::Failure(ParseFailure::no_progress::<P>(cloned_position));
+                }
+                ParseResult::Success(rhs) => {
+                    operators.push(operator);
+                    operands.push(rhs);
+                }
+                ParseResult::Failure(f) if f.committed => return ParseResult::Failure(f),
+                ParseResult::Failure(_) => {
+                    // a dangling operator is not part of the chain
+                    position.advance_to(cloned_position);
+                    context.truncate_diagnostics(diagnostics);
+                    break;
+                }
+            }
+        }
+
+        // the operands are folded from the right, `a ^ b ^ c` is `a ^ (b ^ c)`
+        let mut acc = operands.pop().unwrap();
+        while let Some(operator) = operators.pop() {
+            acc = operator(operands.pop().unwrap(), acc);
+        }
+        ParseResult::Success(acc)
+    }
+}
@@ -0,0 +1,277 @@
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Formatter},
+    marker::PhantomData,
+    ptr::NonNull,
+};
+
+use crate::{
+    ParseFailure, ParseResult, ParsingPosition,
+    parsable::{self, TextParsable},
+};
+
+/// A boxed value whose type only the parser that stored it knows. Unlike `Box<dyn Any>` it can
+/// hold outputs that borrow from the input of lifetime `'a`.
+pub(crate) struct ErasedBox<'a> {
+    ptr: NonNull<()>,
+    drop: unsafe fn(NonNull<()>),
+    __phantom1: PhantomData<&'a ()>,
+}
+
+impl<'a> ErasedBox<'a> {
+    pub fn new<T: 'a>(value: T) -> Self {
+        ErasedBox {
+            ptr: NonNull::from(Box::leak(Box::new(value))).cast(),
+            drop: drop_erased::<T>,
+            __phantom1: PhantomData,
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `T` must be the type the box was created with.
+    pub unsafe fn downcast_ref<T: 'a>(&self) -> &T {
+        // SAFETY: the caller guarantees the type, the box is alive as long as `self`
+        unsafe { self.ptr.cast::<T>().as_ref() }
+    }
+}
+
+unsafe fn drop_erased<T>(ptr: NonNull<()>) {
+    // SAFETY: only called by `ErasedBox::drop` with the pointer `ErasedBox::new::<T>` leaked
+    drop(unsafe { Box::from_raw(ptr.cast::<T>().as_ptr()) });
+}
+
+impl Drop for ErasedBox<'_> {
+    fn drop(&mut self) {
+        // SAFETY: `drop` was created for the type `ptr` points to
+        unsafe { (self.drop)(self.ptr) }
+    }
+}
+
+/// A cached parse, see `ParserCombinator::memoize`.
+pub(crate) struct MemoEntry<'a> {
+    /// The `ParseResult` of the memoized parser, always of the type the parser with the id of the
+    /// entry outputs for input of lifetime `'a`.
+    pub result: ErasedBox<'a>,
+    pub end: ParsingPosition,
+    /// The diagnostics recorded while parsing, replayed on every hit.
+    pub diagnostics: Vec<ParseFailure>,
+}
+
+/// A `RecRefParser` call that has not returned yet, used to detect left recursion.
+pub(crate) struct RecursionFrame<'a> {
+    pub parser_id: usize,
+    pub index: usize,
+    pub left_recursive: bool,
+    /// The best `ParseResult` so far and where it ended, set once the call has a seed. The result
+    /// is of the output type of the parser with `parser_id`.
+    pub seed: Option<(ErasedBox<'a>, ParsingPosition)>,
+}
+
+/// Mutable state shared by all parsers during a single parse of input of lifetime `'a`.
+///
+/// The context is invariant in `'a`, the memo entries it keeps may borrow from the input and are
+/// only ever read back by parses of input of the same lifetime.
+pub struct ParseContext<'a> {
+    diagnostics: Vec<ParseFailure>,
+    /// Memo entries with the generation they were written in.
+    memo: HashMap<(usize, usize), (u64, MemoEntry<'a>)>,
+    /// Bumped whenever a left recursive seed grows, see `invalidate_memo_at`.
+    generation: u64,
+    /// The generation in which memo entries at an index were last invalidated.
+    invalidated: HashMap<usize, u64>,
+    recursion_stack: Vec<RecursionFrame<'a>>,
+    partial: bool,
+    __phantom1: PhantomData<fn(&'a ()) -> &'a ()>,
+}
+
+impl Default for ParseContext<'_> {
+    fn default() -> Self {
+        ParseContext {
+            diagnostics: Vec::new(),
+            memo: HashMap::new(),
+            generation: 0,
+            invalidated: HashMap::new(),
+            recursion_stack: Vec::new(),
+            partial: false,
+            __phantom1: PhantomData,
+        }
+    }
+}
+
+impl<'a> ParseContext<'a> {
+    pub fn new() -> Self {
+        ParseContext::default()
+    }
+
+    /// Records a failure that a parser recovered from.
+    pub fn push_diagnostic(&mut self, failure: ParseFailure) {
+        self.diagnostics.push(failure);
+    }
+
+    pub fn diagnostics(&self) -> &[ParseFailure] {
+        &self.diagnostics
+    }
+
+    pub fn take_diagnostics(&mut self) -> Vec<ParseFailure> {
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    #[inline]
+    pub fn diagnostics_len(&self) -> usize {
+        self.diagnostics.len()
+    }
+
+    /// Drops the diagnostics recorded after `len`, used when backtracking out of a branch.
+    #[inline]
+    pub fn truncate_diagnostics(&mut self, len: usize) {
+        // most branches record none, this runs on every backtrack
+        if self.diagnostics.len() > len {
+            self.diagnostics.truncate(len);
+        }
+    }
+
+    /// Whether more input may follow the buffer being parsed, see `stream::StreamParser`.
+    #[inline]
+    pub fn is_partial(&self) -> bool {
+        self.partial
+    }
+
+    pub fn set_partial(&mut self, partial: bool) {
+        self.partial = partial;
+    }
+
+    /// In partial mode, the failure for a parser that stopped at `index` because it is the end of
+    /// the buffer and needs at least `needed` more items.
+    pub fn incomplete_at<T, U>(
+        &self,
+        input: &[T],
+        index: usize,
+        needed: usize,
+    ) -> Option<ParseResult<U>> {
+        if self.partial && index >= input.len() {
+            Some(ParseResult::Failure(ParseFailure::incomplete_at(
+                input.len().into(),
+                needed,
+            )))
+        } else {
+            None
+        }
+    }
+
+    /// Like `incomplete_at`, for a scan of text that stopped at `index`, which may be in the middle
+    /// of a multi-byte character cut off by the end of the buffer.
+    pub(crate) fn incomplete_text<TIn: TextParsable, U>(
+        &self,
+        input: &[TIn::T],
+        index: usize,
+    ) -> Option<ParseResult<U>> {
+        if !self.partial {
+            return None;
+        }
+        self.incomplete_at(input, parsable::scan_end::<TIn>(input, index), 1)
+    }
+
+    /// Like `incomplete_at`, for a literal that the rest of the buffer is a prefix of.
+    pub fn incomplete_literal<T: Eq, U>(
+        &self,
+        input: &[T],
+        position: &ParsingPosition,
+        literal: &[T],
+    ) -> Option<ParseResult<U>> {
+        if !self.partial {
+            return None;
+        }
+
+        let rest = position.slice(input);
+        if rest.len() < literal.len() && literal.starts_with(rest) {
+            self.incomplete_at(input, input.len(), literal.len() - rest.len())
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn memo_get(
+        &self,
+        parser_id: usize,
+        position: &ParsingPosition,
+    ) -> Option<&MemoEntry<'a>> {
+        let (generation, entry) = self.memo.get(&(parser_id, position.index()))?;
+        match self.invalidated.get(&position.index()) {
+            Some(invalidated) if invalidated > generation => None,
+            _ => Some(entry),
+        }
+    }
+
+    pub(crate) fn memo_insert(
+        &mut self,
+        parser_id: usize,
+        position: &ParsingPosition,
+        entry: MemoEntry<'a>,
+    ) {
+        self.memo
+            .insert((parser_id, position.index()), (self.generation, entry));
+    }
+
+    /// Marks the memo entries at `index` as stale, called when the seed of a left recursive call
+    /// at `index` grows. Only entries at that index can have seen the old seed, calls at later
+    /// indices push their own recursion frames.
+    pub(crate) fn invalidate_memo_at(&mut self, index: usize) {
+        self.generation += 1;
+        self.invalidated.insert(index, self.generation);
+    }
+
+    pub fn memo_len(&self) -> usize {
+        self.memo.len()
+    }
+
+    pub fn clear_memo(&mut self) {
+        self.memo.clear();
+        self.invalidated.clear();
+    }
+
+    /// Finds the active call of `parser_id` at `index`. Calls only ever move forward, so only
+    /// the frames at the top of the stack can be at the same index.
+    pub(crate) fn recursion_frame(
+        &mut self,
+        parser_id: usize,
+        index: usize,
+    ) -> Option<&mut RecursionFrame<'a>> {
+        self.recursion_stack
+            .iter_mut()
+            .rev()
+            .take_while(|frame| frame.index == index)
+            .find(|frame| frame.parser_id == parser_id)
+    }
+
+    pub(crate) fn push_recursion_frame(&mut self, parser_id: usize, index: usize) {
+        self.recursion_stack.push(RecursionFrame {
+            parser_id,
+            index,
+            left_recursive: false,
+            seed: None,
+        });
+    }
+
+    /// The frame pushed last, which is the caller's own frame once its nested calls returned.
+    pub(crate) fn top_recursion_frame(&mut self) -> &mut RecursionFrame<'a> {
+        self.recursion_stack
+            .last_mut()
+            .expect("recursion stack is empty")
+    }
+
+    pub(crate) fn pop_recursion_frame(&mut self) {
+        self.recursion_stack.pop();
+    }
+}
+
+impl Debug for ParseContext<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ParseContext")
+            .field("diagnostics", &self.diagnostics)
+            .field("memo_len", &self.memo.len())
+            .field("recursion_depth", &self.recursion_stack.len())
+            .field("partial", &self.partial)
+            .finish()
+    }
+}
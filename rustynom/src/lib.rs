@@ -1,9 +1,16 @@
 use parsable::Parsable;
 
 pub mod atomic_parsers;
+pub mod binary_parsers;
 pub mod combinator_parsers;
+pub mod context;
+pub mod location;
+pub mod number_parsers;
 pub mod parsable;
 pub mod parser;
+pub mod recovery_parsers;
+pub mod report;
+pub mod stream;
 pub mod transformation_parsers;
 pub mod utility_parsers;
 
@@ -105,6 +112,7 @@ impl ParsingPosition {
         ParseResult::Failure(ParseFailure::new(self.clone(), expected))
     }
 
+    #[inline]
     pub fn merge_failures(&self, mut a: ParseFailure, b: ParseFailure) -> ParseFailure {
         match a.furthest.cmp(&b.furthest) {
             std::cmp::Ordering::Less => b,
@@ -116,7 +124,9 @@ impl ParsingPosition {
                     _ => {}
                 }
 
-                // a.expected.extend(b.expected);
+                if let Some(b) = b.details {
+                    a.merge_details(*b);
+                }
                 a
             }
         }
@@ -141,15 +151,187 @@ pub struct ParsingRange {
     pub to: ParsingPosition,
 }
 
+impl ParsingRange {
+    pub fn new(from: ParsingPosition, to: ParsingPosition) -> Self {
+        ParsingRange { from, to }
+    }
+
+    pub fn len(&self) -> usize {
+        self.to.index() - self.from.index()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn slice<'a, T>(&self, slice: &'a [T]) -> &'a [T] {
+        self.from.slice_to(slice, self.to.index())
+    }
+
+    /// The smallest range covering both ranges.
+    pub fn merge(&self, other: &ParsingRange) -> ParsingRange {
+        ParsingRange {
+            from: self.from.clone().min(other.from.clone()),
+            to: self.to.clone().max(other.to.clone()),
+        }
+    }
+}
+
+/// A parser output together with the range of input it was parsed from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spanned<T> {
+    pub value: T,
+    pub range: ParsingRange,
+}
+
+impl<T> Spanned<T> {
+    pub fn new(value: T, range: ParsingRange) -> Self {
+        Spanned { value, range }
+    }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Spanned<U> {
+        Spanned {
+            value: f(self.value),
+            range: self.range,
+        }
+    }
+
+    /// Combines two spanned values into one covering both ranges, e.g. the operands of a binary
+    /// expression.
+    pub fn merge<U, V>(self, other: Spanned<U>, f: impl FnOnce(T, U) -> V) -> Spanned<V> {
+        Spanned {
+            range: self.range.merge(&other.range),
+            value: f(self.value, other.value),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ParseFailure {
     pub furthest: ParsingPosition,
     pub expected: Option<Vec<String>>,
+    /// Set by `ParserCombinator::cut`, committed failures are not backtracked out of.
+    pub committed: bool,
+    /// Boxed, most failures are backtracked out of and never have any.
+    details: Option<Box<FailureDetails>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+struct FailureDetails {
+    context: Vec<String>,
+    error: Option<String>,
+    span: Option<ParsingRange>,
+    incomplete: Option<usize>,
 }
 
 impl ParseFailure {
     pub fn new(furthest: ParsingPosition, expected: Option<Vec<String>>) -> ParseFailure {
-        ParseFailure { furthest, expected }
+        ParseFailure {
+            furthest,
+            expected,
+            committed: false,
+            details: None,
+        }
+    }
+
+    /// A committed failure, no alternative may be tried before the rest of the input is known.
+    #[cold]
+    pub fn incomplete_at(position: ParsingPosition, needed: usize) -> ParseFailure {
+        ParseFailure {
+            committed: true,
+            details: Some(Box::new(FailureDetails {
+                incomplete: Some(needed),
+                ..FailureDetails::default()
+            })),
+            ..ParseFailure::new(position, None)
+        }
+    }
+
+    #[cold]
+    pub fn with_error(span: ParsingRange, error: String) -> ParseFailure {
+        ParseFailure {
+            details: Some(Box::new(FailureDetails {
+                error: Some(error),
+                span: Some(span.clone()),
+                ..FailureDetails::default()
+            })),
+            ..ParseFailure::new(span.from, None)
+        }
+    }
+
+    /// A failure expecting `expected` instead of the input in `span`, which was read but rejected.
+    #[cold]
+    pub fn with_expected(span: ParsingRange, expected: Vec<String>) -> ParseFailure {
+        ParseFailure {
+            details: Some(Box::new(FailureDetails {
+                span: Some(span.clone()),
+                ..FailureDetails::default()
+            })),
+            ..ParseFailure::new(span.from, Some(expected))
+        }
+    }
+
+    /// Context frames added by `ParserCombinator::context`, innermost first.
+    pub fn context(&self) -> &[String] {
+        self.details.as_ref().map_or(&[], |d| &d.context)
+    }
+
+    pub fn push_context(&mut self, context: String) {
+        self.details.get_or_insert_default().context.push(context);
+    }
+
+    /// Replaces the expected items for failures on input that was read but rejected, e.g. a
+    /// number that does not fit its type.
+    pub fn error(&self) -> Option<&str> {
+        self.details.as_ref()?.error.as_deref()
+    }
+
+    /// The rejected input `error` refers to.
+    pub fn span(&self) -> Option<&ParsingRange> {
+        self.details.as_ref()?.span.as_ref()
+    }
+
+    pub fn span_mut(&mut self) -> Option<&mut ParsingRange> {
+        self.details.as_mut()?.span.as_mut()
+    }
+
+    /// Set in partial mode when the parser ran into the end of the buffer, with the number of
+    /// items it needs at least to continue.
+    pub fn incomplete(&self) -> Option<usize> {
+        self.details.as_ref()?.incomplete
+    }
+
+    /// The error and context of a branch that got this far describe the failure better than the
+    /// expected items alone, keep them from whichever side has them.
+    #[cold]
+    fn merge_details(&mut self, other: FailureDetails) {
+        let details = self.details.get_or_insert_default();
+        if details.error.is_none() {
+            details.error = other.error;
+            details.span = other.span;
+        }
+        if details.context.is_empty() {
+            details.context = other.context;
+        }
+    }
+
+    /// The committed failure returned by repetition combinators whose parser `P` succeeded
+    /// without consuming anything, which would otherwise loop forever. Debug builds name the
+    /// parser in the message.
+    pub(crate) fn no_progress<P: ?Sized>(position: ParsingPosition) -> ParseFailure {
+        let error = if cfg!(debug_assertions) {
+            format!(
+                "repeated parser succeeded without consuming input: {}",
+                std::any::type_name::<P>()
+            )
+        } else {
+            "repeated parser succeeded without consuming input".to_string()
+        };
+
+        ParseFailure {
+            committed: true,
+            ..ParseFailure::with_error(ParsingRange::new(position.clone(), position), error)
+        }
     }
 }
 
@@ -192,6 +374,15 @@ impl<T, F> GenericParseResult<T, F> {
 
 pub type ParseResult<T> = GenericParseResult<T, ParseFailure>;
 
+impl<T> ParseResult<T> {
+    pub fn is_committed(&self) -> bool {
+        match self {
+            GenericParseResult::Success(_) => false,
+            GenericParseResult::Failure(f) => f.committed,
+        }
+    }
+}
+
 #[macro_export]
 macro_rules! parse_str {
     ($p:expr, $str:literal) => {{
@@ -0,0 +1,129 @@
+use std::fmt::Display;
+
+use crate::{ParseFailure, ParsingPosition, ParsingRange, parsable::Parsable};
+
+/// A 1-based line and column, columns are counted in characters for text and in items otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LineColumn {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl LineColumn {
+    pub fn new(line: usize, column: usize) -> Self {
+        LineColumn { line, column }
+    }
+}
+
+impl Display for LineColumn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Maps input indices to lines and columns.
+/// Build it once per input and reuse it for every position that needs resolving.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIndex {
+    line_starts: Vec<usize>,
+    /// The indices of items that continue a character, see `Parsable::is_continuation`.
+    continuations: Vec<usize>,
+    len: usize,
+}
+
+impl LineIndex {
+    pub fn new<TIn: Parsable>(input: &[TIn::T]) -> Self {
+        let mut line_starts = vec![0];
+        let mut continuations = Vec::new();
+        for (index, t) in input.iter().enumerate() {
+            if TIn::is_line_break(t) {
+                line_starts.push(index + 1);
+            } else if TIn::is_continuation(t) {
+                continuations.push(index);
+            }
+        }
+
+        LineIndex {
+            line_starts,
+            continuations,
+            len: input.len(),
+        }
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    pub fn input_len(&self) -> usize {
+        self.len
+    }
+
+    pub fn line_column(&self, position: &ParsingPosition) -> LineColumn {
+        let index = position.index().min(self.len);
+        let line = match self.line_starts.binary_search(&index) {
+            Ok(line) => line,
+            Err(line) => line - 1,
+        };
+
+        let start = self.line_starts[line];
+        let continued = self.continuations.partition_point(|i| *i < index)
+            - self.continuations.partition_point(|i| *i < start);
+        LineColumn::new(line + 1, index - start - continued + 1)
+    }
+
+    /// The range of the given 1-based line, excluding the line break.
+    pub fn line_range(&self, line: usize) -> ParsingRange {
+        let from = self.line_starts[line - 1];
+        let to = match self.line_starts.get(line) {
+            Some(next) => next - 1,
+            None => self.len,
+        };
+
+        ParsingRange::new(from.into(), to.into())
+    }
+
+    pub fn span(&self, range: &ParsingRange) -> Span {
+        Span {
+            range: range.clone(),
+            start: self.line_column(&range.from),
+            end: self.line_column(&range.to),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span {
+    pub range: ParsingRange,
+    pub start: LineColumn,
+    pub end: LineColumn,
+}
+
+impl Span {
+    pub fn is_multiline(&self) -> bool {
+        self.start.line != self.end.line
+    }
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+impl ParsingPosition {
+    pub fn line_column(&self, line_index: &LineIndex) -> LineColumn {
+        line_index.line_column(self)
+    }
+}
+
+impl ParsingRange {
+    pub fn span(&self, line_index: &LineIndex) -> Span {
+        line_index.span(self)
+    }
+}
+
+impl ParseFailure {
+    pub fn line_column(&self, line_index: &LineIndex) -> LineColumn {
+        line_index.line_column(&self.furthest)
+    }
+}
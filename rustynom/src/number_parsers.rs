@@ -0,0 +1,596 @@
+use fast_float::FastFloat;
+
+use crate::{
+    ParseFailure, ParseResult, ParsingPosition, ParsingRange, context::ParseContext,
+    parsable::TextParsable, parser::RawTestParser,
+};
+
+// ---------------
+// Float parser
+// ---------------
+
+/// The grammar accepted by a [`FloatParser`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FloatFormat {
+    pub minus_sign: bool,
+    pub plus_sign: bool,
+    /// `.5`
+    pub leading_dot: bool,
+    /// `1.`, unless the dot is followed by `.`, `_` or a letter as in `1..2` or `1.max(2)`
+    pub trailing_dot: bool,
+    /// `007`
+    pub leading_zeros: bool,
+    /// `1e10`
+    pub exponent: bool,
+    /// `1_000`, only between or after digits
+    pub underscores: bool,
+    /// `inf`, `infinity` and `nan`, case insensitive
+    pub special_values: bool,
+}
+
+impl FloatFormat {
+    /// `-?(0|[1-9][0-9]*)(\.[0-9]+)?([eE][+-]?[0-9]+)?`
+    pub const JSON: FloatFormat = FloatFormat {
+        minus_sign: true,
+        plus_sign: false,
+        leading_dot: false,
+        trailing_dot: false,
+        leading_zeros: false,
+        exponent: true,
+        underscores: false,
+        special_values: false,
+    };
+
+    /// Unsuffixed Rust float literals, e.g. `1_000.5e-3`.
+    pub const RUST: FloatFormat = FloatFormat {
+        minus_sign: false,
+        plus_sign: false,
+        leading_dot: false,
+        trailing_dot: true,
+        leading_zeros: true,
+        exponent: true,
+        underscores: true,
+        special_values: false,
+    };
+
+    /// Everything `f64::from_str` accepts, plus digit separators. A trailing dot is not taken
+    /// before an exponent, `1.e5` parses as `1`.
+    pub const PERMISSIVE: FloatFormat = FloatFormat {
+        minus_sign: true,
+        plus_sign: true,
+        leading_dot: true,
+        trailing_dot: true,
+        leading_zeros: true,
+        exponent: true,
+        underscores: true,
+        special_values: true,
+    };
+
+    pub fn signed(mut self, signed: bool) -> Self {
+        self.minus_sign = signed;
+        self.plus_sign = signed;
+        self
+    }
+}
+
+/// Float types a [`FloatParser`] can produce.
+pub trait Float: FastFloat {
+    /// `mantissa * 10^scale`, if it can be computed without rounding errors.
+    fn exact(negative: bool, mantissa: u64, scale: i64) -> Option<Self>;
+}
+
+macro_rules! impl_float {
+    ($($t:ident: $mantissa_bits:expr, $max_power:expr);*) => {
+        $(
+            impl Float for $t {
+                // Clinger's fast path, the mantissa and the power of ten are both exact, so a
+                // single multiplication or division rounds correctly
+                fn exact(negative: bool, mantissa: u64, scale: i64) -> Option<Self> {
+                    if mantissa >= 1 << $mantissa_bits || scale.unsigned_abs() > $max_power {
+                        return None;
+                    }
+
+                    let power = POWERS_OF_TEN[scale.unsigned_abs() as usize] as $t;
+                    let value = if scale < 0 {
+                        mantissa as $t / power
+                    } else {
+                        mantissa as $t * power
+                    };
+                    Some(if negative { -value } else { value })
+                }
+            }
+        )*
+    };
+}
+
+impl_float!(f32: 24, 10; f64: 53, 22);
+
+const POWERS_OF_TEN: [f64; 23] = [
+    1e0, 1e1, 1e2, 1e3, 1e4, 1e5, 1e6, 1e7, 1e8, 1e9, 1e10, 1e11, 1e12, 1e13, 1e14, 1e15, 1e16,
+    1e17, 1e18, 1e19, 1e20, 1e21, 1e22,
+];
+
+/// A number matched by `scan_float`.
+pub(crate) struct ScannedFloat {
+    start: usize,
+    /// The index after the number.
+    pub end: usize,
+    negative: bool,
+    /// The mantissa and power of ten, `None` for special values and mantissas too long for a `u64`.
+    decimal: Option<(u64, i64)>,
+}
+
+impl ScannedFloat {
+    pub fn value<TIn: TextParsable, N: Float>(&self, input: &[TIn::T]) -> Option<N> {
+        if let Some((mantissa, scale)) = self.decimal
+            && let Some(value) = N::exact(self.negative, mantissa, scale)
+        {
+            return Some(value);
+        }
+
+        // everything else is parsed from the matched text
+        let mut buf = Vec::with_capacity(self.end - self.start);
+        let mut index = self.start;
+        while index < self.end {
+            let (c, width) = TIn::next_char(input, index)?;
+            index += width;
+            match c {
+                '_' => {}
+                // a trailing dot is accepted by the format but not by `fast_float`
+                '.' if !matches!(TIn::next_char(input, index), Some((c, _)) if c.is_ascii_digit()) =>
+                    {}
+                c => buf.push(c.to_ascii_lowercase() as u8),
+            }
+        }
+        fast_float::parse(buf).ok()
+    }
+}
+
+/// The digits read by `scan_digits`.
+#[derive(Default)]
+struct Digits {
+    value: u64,
+    count: u32,
+}
+
+impl Digits {
+    fn push(&mut self, digit: u32) {
+        // 19 digits always fit, longer values are left to `fast_float`
+        if self.count < 19 {
+            self.value = self.value * 10 + digit as u64;
+        }
+        self.count += 1;
+    }
+
+    fn value(&self) -> Option<u64> {
+        (self.count <= 19).then_some(self.value)
+    }
+}
+
+/// Scans the longest prefix starting at `index` that matches `format`.
+pub(crate) fn scan_float<TIn: TextParsable>(
+    format: &FloatFormat,
+    input: &[TIn::T],
+    start: usize,
+) -> Option<ScannedFloat> {
+    let mut index = start;
+    let negative = match TIn::next_char(input, index) {
+        Some(('-', width)) if format.minus_sign => {
+            index += width;
+            true
+        }
+        Some(('+', width)) if format.plus_sign => {
+            index += width;
+            false
+        }
+        _ => false,
+    };
+
+    if format.special_values {
+        for word in ["infinity", "inf", "nan"] {
+            if let Some(end) = scan_word::<TIn>(input, index, word) {
+                return Some(ScannedFloat {
+                    start,
+                    end,
+                    negative,
+                    decimal: None,
+                });
+            }
+        }
+    }
+
+    let mut mantissa = Digits::default();
+    index = match TIn::next_char(input, index) {
+        Some(('0', width)) if !format.leading_zeros => {
+            mantissa.push(0);
+            index + width
+        }
+        _ => scan_digits::<TIn>(format, input, index, &mut mantissa),
+    };
+    let int_digits = mantissa.count;
+
+    if int_digits == 0 && !format.leading_dot {
+        return None;
+    }
+
+    if let Some(('.', width)) = TIn::next_char(input, index) {
+        match TIn::next_char(input, index + width) {
+            Some((c, _)) if c.is_ascii_digit() => {
+                index = scan_digits::<TIn>(format, input, index + width, &mut mantissa);
+            }
+            // the dot starts a range or a field or method access, as in Rust's lexer
+            Some((c, _)) if c == '.' || c == '_' || c.is_alphabetic() => {}
+            _ if format.trailing_dot && int_digits > 0 => index += width,
+            _ => {}
+        }
+    }
+    let frac_digits = mantissa.count - int_digits;
+
+    if mantissa.count == 0 {
+        return None;
+    }
+
+    let mut exponent = Digits::default();
+    let mut exponent_negative = false;
+    if format.exponent
+        && let Some(('e' | 'E', width)) = TIn::next_char(input, index)
+    {
+        let mut exponent_index = index + width;
+        if let Some((sign @ ('+' | '-'), width)) = TIn::next_char(input, exponent_index) {
+            exponent_negative = sign == '-';
+            exponent_index += width;
+        }
+
+        exponent_index = scan_digits::<TIn>(format, input, exponent_index, &mut exponent);
+
+        // a dangling `e` is not part of the number
+        if exponent.count > 0 {
+            index = exponent_index;
+        }
+    }
+
+    let decimal = mantissa
+        .value()
+        .zip(exponent.value())
+        .map(|(mantissa, exponent)| {
+            let exponent = i64::try_from(exponent).unwrap_or(i64::MAX);
+            let exponent = if exponent_negative {
+                -exponent
+            } else {
+                exponent
+            };
+            (mantissa, exponent.saturating_sub(frac_digits as i64))
+        });
+    Some(ScannedFloat {
+        start,
+        end: index,
+        negative,
+        decimal,
+    })
+}
+
+fn scan_digits<TIn: TextParsable>(
+    format: &FloatFormat,
+    input: &[TIn::T],
+    mut index: usize,
+    digits: &mut Digits,
+) -> usize {
+    let start = digits.count;
+    while let Some((c, width)) = TIn::next_char(input, index) {
+        if let Some(digit) = c.to_digit(10) {
+            digits.push(digit);
+        } else if c != '_' || !format.underscores || digits.count == start {
+            break;
+        }
+        index += width;
+    }
+    index
+}
+
+/// In partial mode, a number that runs into the end of the buffer may continue in the next chunk.
+pub(crate) fn incomplete_number<TIn: TextParsable, U>(
+    context: &ParseContext,
+    input: &[TIn::T],
+    mut index: usize,
+) -> Option<ParseResult<U>> {
+    if !context.is_partial() {
+        return None;
+    }
+
+    while let Some((c, width)) = TIn::next_char(input, index) {
+        if !c.is_ascii_alphanumeric() && !matches!(c, '+' | '-' | '.' | '_') {
+            break;
+        }
+        index += width;
+    }
+    context.incomplete_text::<TIn, _>(input, index)
+}
+
+fn scan_word<TIn: TextParsable>(input: &[TIn::T], mut index: usize, word: &str) -> Option<usize> {
+    for expected in word.chars() {
+        let (c, width) = TIn::next_char(input, index)?;
+        if !c.eq_ignore_ascii_case(&expected) {
+            return None;
+        }
+        index += width;
+    }
+    Some(index)
+}
+
+#[derive(Clone)]
+pub struct FloatParser<TIn: TextParsable = char, N: Float = f64> {
+    format: FloatFormat,
+    __phantom1: std::marker::PhantomData<TIn>,
+    __phantom2: std::marker::PhantomData<N>,
+}
+
+impl<TIn: TextParsable, N: Float> FloatParser<TIn, N> {
+    pub fn new(format: FloatFormat) -> Self {
+        FloatParser {
+            format,
+            __phantom1: std::marker::PhantomData,
+            __phantom2: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<'a, TIn: TextParsable, N: Float, const ERROR: bool> RawTestParser<'a, TIn, ERROR>
+    for FloatParser<TIn, N>
+{
+    type TOut = N;
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<N> {
+        if let Some(incomplete) = incomplete_number::<TIn, _>(context, input, position.index()) {
+            return incomplete;
+        }
+
+        let parsed = scan_float::<TIn>(&self.format, input, position.index())
+            .and_then(|scan| Some((scan.value::<TIn, N>(input)?, scan.end)));
+
+        match parsed {
+            Some((n, end)) => position.succeed_at(end.into(), n),
+            None => {
+                let error = if ERROR {
+                    Some(vec!["a number".to_string()])
+                } else {
+                    None
+                };
+                position.fail_offset(0, error)
+            }
+        }
+    }
+}
+
+pub fn float_with<TIn: TextParsable, N: Float>(format: FloatFormat) -> FloatParser<TIn, N> {
+    FloatParser::new(format)
+}
+
+pub fn json_number() -> FloatParser {
+    FloatParser::new(FloatFormat::JSON)
+}
+
+pub fn rust_float() -> FloatParser {
+    FloatParser::new(FloatFormat::RUST)
+}
+
+pub fn permissive_float() -> FloatParser {
+    FloatParser::new(FloatFormat::PERMISSIVE)
+}
+
+// ---------------
+// Integer parser
+// ---------------
+
+pub trait Integer: Copy {
+    const SIGNED: bool;
+    const ZERO: Self;
+
+    /// Appends a digit, subtracting it instead for negative numbers. `None` on overflow.
+    fn push_digit(self, radix: u32, digit: u32, negative: bool) -> Option<Self>;
+}
+
+macro_rules! impl_integer {
+    ($($t:ident),*) => {
+        $(
+            impl Integer for $t {
+                const SIGNED: bool = $t::MIN != 0;
+                const ZERO: Self = 0;
+
+                #[inline]
+                fn push_digit(self, radix: u32, digit: u32, negative: bool) -> Option<Self> {
+                    let shifted = self.checked_mul(radix as $t)?;
+                    if negative {
+                        shifted.checked_sub(digit as $t)
+                    } else {
+                        shifted.checked_add(digit as $t)
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
+);
+
+/// The grammar accepted by an [`IntParser`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntFormat {
+    /// Only has an effect for signed types.
+    pub minus_sign: bool,
+    pub plus_sign: bool,
+    /// The radix of numbers without a prefix.
+    pub radix: u32,
+    /// `0x`, `0o` and `0b`, case insensitive
+    pub radix_prefixes: bool,
+    /// `1_000`, only between or after digits
+    pub underscores: bool,
+}
+
+impl IntFormat {
+    pub const DECIMAL: IntFormat = IntFormat {
+        minus_sign: true,
+        plus_sign: false,
+        radix: 10,
+        radix_prefixes: false,
+        underscores: false,
+    };
+
+    /// Rust integer literals without a suffix, e.g. `0xff_ff`.
+    pub const RUST: IntFormat = IntFormat {
+        minus_sign: false,
+        plus_sign: false,
+        radix: 10,
+        radix_prefixes: true,
+        underscores: true,
+    };
+
+    pub fn signed(mut self, signed: bool) -> Self {
+        self.minus_sign = signed;
+        self.plus_sign = signed;
+        self
+    }
+
+    pub fn radix(mut self, radix: u32) -> Self {
+        assert!((2..=36).contains(&radix), "radix must be between 2 and 36");
+        self.radix = radix;
+        self
+    }
+}
+
+pub(crate) enum IntScan<N> {
+    NoDigits,
+    /// The number did not fit, the index is the end of its digits.
+    Overflow(usize, bool),
+    Success(N, usize),
+}
+
+pub(crate) fn scan_int<TIn: TextParsable, N: Integer>(
+    format: &IntFormat,
+    input: &[TIn::T],
+    mut index: usize,
+) -> IntScan<N> {
+    let mut negative = false;
+    match TIn::next_char(input, index) {
+        Some(('-', width)) if format.minus_sign && N::SIGNED => {
+            negative = true;
+            index += width;
+        }
+        Some(('+', width)) if format.plus_sign => index += width,
+        _ => {}
+    }
+
+    let mut radix = format.radix;
+    if format.radix_prefixes
+        && let Some(('0', zero_width)) = TIn::next_char(input, index)
+    {
+        let prefix_radix = match TIn::next_char(input, index + zero_width) {
+            Some(('x' | 'X', width)) => Some((16, width)),
+            Some(('o' | 'O', width)) => Some((8, width)),
+            Some(('b' | 'B', width)) => Some((2, width)),
+            _ => None,
+        };
+
+        // without a digit after it, the prefix is a plain `0` followed by something else
+        if let Some((prefix_radix, width)) = prefix_radix
+            && let Some((c, _)) = TIn::next_char(input, index + zero_width + width)
+            && c.is_digit(prefix_radix)
+        {
+            radix = prefix_radix;
+            index += zero_width + width;
+        }
+    }
+
+    let mut value = Some(N::ZERO);
+    let mut digits = 0;
+    while let Some((c, width)) = TIn::next_char(input, index) {
+        if let Some(digit) = c.to_digit(radix) {
+            value = value.and_then(|value| value.push_digit(radix, digit, negative));
+            digits += 1;
+        } else if c != '_' || !format.underscores || digits == 0 {
+            break;
+        }
+        index += width;
+    }
+
+    match value {
+        _ if digits == 0 => IntScan::NoDigits,
+        Some(value) => IntScan::Success(value, index),
+        None => IntScan::Overflow(index, negative),
+    }
+}
+
+#[derive(Clone)]
+pub struct IntParser<TIn: TextParsable = char, N: Integer = i64> {
+    format: IntFormat,
+    __phantom1: std::marker::PhantomData<TIn>,
+    __phantom2: std::marker::PhantomData<N>,
+}
+
+impl<TIn: TextParsable, N: Integer> IntParser<TIn, N> {
+    pub fn new(format: IntFormat) -> Self {
+        IntParser {
+            format,
+            __phantom1: std::marker::PhantomData,
+            __phantom2: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<'a, TIn: TextParsable, N: Integer, const ERROR: bool> RawTestParser<'a, TIn, ERROR>
+    for IntParser<TIn, N>
+{
+    type TOut = N;
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<N> {
+        if let Some(incomplete) = incomplete_number::<TIn, _>(context, input, position.index()) {
+            return incomplete;
+        }
+
+        match scan_int::<TIn, N>(&self.format, input, position.index()) {
+            IntScan::Success(n, end) => position.succeed_at(end.into(), n),
+            IntScan::Overflow(end, negative) if ERROR => {
+                let error = if negative {
+                    "number too small"
+                } else {
+                    "number too large"
+                };
+                let span = ParsingRange::new(position.clone(), end.into());
+                ParseResult::Failure(ParseFailure::with_error(span, error.to_string()))
+            }
+            IntScan::Overflow(_, _) => position.fail_offset(0, None),
+            IntScan::NoDigits => {
+                let error = if ERROR {
+                    Some(vec!["an integer".to_string()])
+                } else {
+                    None
+                };
+                position.fail_offset(0, error)
+            }
+        }
+    }
+}
+
+pub fn int_with<TIn: TextParsable, N: Integer>(format: IntFormat) -> IntParser<TIn, N> {
+    IntParser::new(format)
+}
+
+/// A decimal integer, signed if `N` is.
+pub fn int<N: Integer>() -> IntParser<char, N> {
+    IntParser::new(IntFormat::DECIMAL)
+}
+
+pub fn rust_int<N: Integer>() -> IntParser<char, N> {
+    IntParser::new(IntFormat::RUST)
+}
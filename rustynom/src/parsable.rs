@@ -1,4 +1,4 @@
-use std::{fmt::Debug, marker::PhantomData};
+use std::{fmt::Debug, marker::PhantomData, str::Utf8Error};
 
 use itertools::Itertools;
 
@@ -8,6 +8,16 @@ pub trait Parsable: Clone {
 
     fn t_to_string(t: &Self::T) -> String;
 
+    fn is_line_break(_t: &Self::T) -> bool {
+        false
+    }
+
+    /// Whether the item continues a character started by the items before it, e.g. the trailing
+    /// bytes of a UTF-8 sequence. Columns don't count these.
+    fn is_continuation(_t: &Self::T) -> bool {
+        false
+    }
+
     fn list_to_owned_slice(list: Self::List) -> Box<[Self::T]>;
     fn list_ref_to_owned_slice(list: &Self::List) -> Box<[Self::T]>;
     fn list_to_string(list: &Self::List) -> String;
@@ -23,6 +33,10 @@ impl Parsable for char {
         t.to_string()
     }
 
+    fn is_line_break(t: &Self::T) -> bool {
+        *t == '\n'
+    }
+
     fn list_to_owned_slice(list: Self::List) -> Box<[Self::T]> {
         list.chars().collect::<Vec<_>>().into_boxed_slice()
     }
@@ -40,6 +54,149 @@ impl Parsable for char {
     }
 }
 
+impl Parsable for u8 {
+    type T = u8;
+    type List = Vec<u8>;
+
+    fn t_to_string(t: &Self::T) -> String {
+        format!("0x{:02x}", t)
+    }
+
+    fn list_to_owned_slice(list: Self::List) -> Box<[Self::T]> {
+        list.into_boxed_slice()
+    }
+
+    fn list_ref_to_owned_slice(list: &Self::List) -> Box<[Self::T]> {
+        Box::from(list.as_slice())
+    }
+
+    fn list_to_string(list: &Self::List) -> String {
+        list.iter().map(Self::t_to_string).join(" ")
+    }
+
+    fn slice_to_list(slice: &[Self::T]) -> Self::List {
+        slice.to_vec()
+    }
+}
+
+/// UTF-8 text parsed directly over its bytes, positions are byte offsets.
+#[derive(Clone)]
+pub struct Utf8;
+
+impl Parsable for Utf8 {
+    type T = u8;
+    type List = String;
+
+    fn t_to_string(t: &Self::T) -> String {
+        String::from_utf8_lossy(&[*t]).into_owned()
+    }
+
+    fn is_line_break(t: &Self::T) -> bool {
+        *t == b'\n'
+    }
+
+    fn is_continuation(t: &Self::T) -> bool {
+        *t & 0xC0 == 0x80
+    }
+
+    fn list_to_owned_slice(list: Self::List) -> Box<[Self::T]> {
+        list.into_bytes().into_boxed_slice()
+    }
+
+    fn list_ref_to_owned_slice(list: &Self::List) -> Box<[Self::T]> {
+        Box::from(list.as_bytes())
+    }
+
+    fn list_to_string(list: &Self::List) -> String {
+        list.clone()
+    }
+
+    fn slice_to_list(slice: &[Self::T]) -> Self::List {
+        String::from_utf8_lossy(slice).into_owned()
+    }
+}
+
+/// Inputs that can be read as a sequence of `char`s.
+pub trait TextParsable: Parsable {
+    /// Decodes the char starting at `index`, returning it together with its width in input items.
+    fn next_char(input: &[Self::T], index: usize) -> Option<(char, usize)>;
+}
+
+impl TextParsable for char {
+    #[inline]
+    fn next_char(input: &[Self::T], index: usize) -> Option<(char, usize)> {
+        input.get(index).map(|c| (*c, 1))
+    }
+}
+
+impl TextParsable for Utf8 {
+    #[inline]
+    fn next_char(input: &[Self::T], index: usize) -> Option<(char, usize)> {
+        let first = *input.get(index)?;
+        if first.is_ascii() {
+            return Some((first as char, 1));
+        }
+
+        let width = match first {
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF7 => 4,
+            _ => return None,
+        };
+        let bytes = input.get(index..index + width)?;
+        let c = std::str::from_utf8(bytes).ok()?.chars().next()?;
+        Some((c, width))
+    }
+}
+
+/// Where a scan that stopped at `index` ends, counting a multi-byte character that is cut off by
+/// the end of the buffer as reaching it.
+pub(crate) fn scan_end<TIn: TextParsable>(input: &[TIn::T], index: usize) -> usize {
+    if TIn::next_char(input, index).is_none() && input.len() - index < 4 {
+        input.len()
+    } else {
+        index
+    }
+}
+
+/// Inputs that can be built from owned text or bytes, see `ParserWrapper::parse_str`.
+pub trait DecodeParsable: Parsable {
+    fn decode_str(input: &str) -> Box<[Self::T]>;
+
+    fn decode_bytes(input: Vec<u8>) -> Result<Box<[Self::T]>, Utf8Error>;
+}
+
+impl DecodeParsable for char {
+    fn decode_str(input: &str) -> Box<[Self::T]> {
+        input.chars().collect()
+    }
+
+    fn decode_bytes(input: Vec<u8>) -> Result<Box<[Self::T]>, Utf8Error> {
+        Ok(std::str::from_utf8(&input)?.chars().collect())
+    }
+}
+
+impl DecodeParsable for u8 {
+    fn decode_str(input: &str) -> Box<[Self::T]> {
+        Box::from(input.as_bytes())
+    }
+
+    fn decode_bytes(input: Vec<u8>) -> Result<Box<[Self::T]>, Utf8Error> {
+        Ok(input.into_boxed_slice())
+    }
+}
+
+impl DecodeParsable for Utf8 {
+    fn decode_str(input: &str) -> Box<[Self::T]> {
+        Box::from(input.as_bytes())
+    }
+
+    fn decode_bytes(input: Vec<u8>) -> Result<Box<[Self::T]>, Utf8Error> {
+        std::str::from_utf8(&input)?;
+        Ok(input.into_boxed_slice())
+    }
+}
+
 pub struct ParsableSlice<T> {
     __phantom: PhantomData<T>,
 }
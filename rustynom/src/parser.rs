@@ -1,49 +1,114 @@
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{self, Read},
+    ops::RangeBounds,
+    path::Path,
+    str::Utf8Error,
+};
+
 use crate::{
-    ParseResult, ParsingPosition,
+    ParseFailure, ParseResult, ParsingPosition, ParsingRange,
     atomic_parsers::EofParser,
     combinator_parsers::{
-        AndParser2, OrParser2, SameOrParser2, SeparatedByParser, SkipParser, SurroundParser,
+        AndParser2, ChainLeftParser, ChainRightParser, CutParser, OrParser2, SameOrParser2,
+        SeparatedByKeepParser, SeparatedByParser, SkipParser, SurroundParser,
+    },
+    context::ParseContext,
+    parsable::{DecodeParsable, Parsable},
+    recovery_parsers::RecoverWithParser,
+    report::FailureReport,
+    transformation_parsers::{
+        AndThenParser, ContextParser, FoldManyParser, LabelParser, ManyNonEmptyParser, ManyParser,
+        MapParser, MemoizeParser, OptionalParser, PeekParser, PredicateParser, RecognizeParser,
+        RepeatArrayParser, RepeatParser, SpannedParser, TryMapParser, VerifyParser,
     },
-    parsable::Parsable,
-    transformation_parsers::{ManyNonEmptyParser, ManyParser, MapParser, OptionalParser},
 };
 
-pub type ParserOut<P, TIn, const ERROR: bool> = <P as RawTestParser<TIn, ERROR>>::TOut;
+pub type ParserOut<'a, P, TIn, const ERROR: bool> = <P as RawTestParser<'a, TIn, ERROR>>::TOut;
 
-pub trait ParserCombinator<TIn: Parsable, const ERROR: bool>: RawTestParser<TIn, ERROR>
+pub trait ParserCombinator<'a, TIn: Parsable, const ERROR: bool>:
+    RawTestParser<'a, TIn, ERROR>
 where
     Self: Sized + Clone,
-    <Self as RawTestParser<TIn, ERROR>>::TOut: Clone,
+    <Self as RawTestParser<'a, TIn, ERROR>>::TOut: Clone,
 {
-    fn and<P2: RawTestParser<TIn, ERROR> + Clone>(
+    fn and<P2: RawTestParser<'a, TIn, ERROR> + Clone>(
         self,
         other: P2,
     ) -> AndParser2<ERROR, TIn, Self, P2> {
         AndParser2::new(self, other)
     }
 
-    fn or<P2: RawTestParser<TIn, ERROR> + Clone>(
+    fn or<P2: RawTestParser<'a, TIn, ERROR> + Clone>(
         self,
         other: P2,
     ) -> OrParser2<ERROR, TIn, Self, P2> {
         OrParser2::new(self, other)
     }
 
-    fn or_same<P2: RawTestParser<TIn, ERROR, TOut = ParserOut<Self, TIn, ERROR>> + Clone>(
+    fn or_same<
+        P2: RawTestParser<'a, TIn, ERROR, TOut = ParserOut<'a, Self, TIn, ERROR>> + Clone,
+    >(
         self,
         other: P2,
     ) -> SameOrParser2<ERROR, TIn, Self, P2> {
         SameOrParser2::new(self, other)
     }
 
-    fn map<TFn: (Fn(ParserOut<Self, TIn, ERROR>) -> TOut) + Clone, TOut: Clone>(
+    fn map<TFn: (Fn(ParserOut<'a, Self, TIn, ERROR>) -> TOut) + Clone, TOut: Clone>(
         self,
         f: TFn,
     ) -> MapParser<TIn, TOut, Self, TFn, ERROR> {
         MapParser::new(self, f)
     }
 
-    fn skip<P2: RawTestParser<TIn, ERROR, TOut = ()> + Clone>(
+    /// Like `map`, but an `Err` fails the parse with the error as message, pointing at the input
+    /// `self` consumed.
+    fn try_map<
+        TFn: (Fn(ParserOut<'a, Self, TIn, ERROR>) -> Result<TOut, TErr>) + Clone,
+        TOut: Clone,
+        TErr: Display,
+    >(
+        self,
+        f: TFn,
+    ) -> TryMapParser<TIn, TOut, TErr, Self, TFn, ERROR> {
+        TryMapParser::new(self, f)
+    }
+
+    /// Fails with `error` pointing at the input `self` consumed if `predicate` rejects the
+    /// output.
+    fn verify<TFn: Fn(&ParserOut<'a, Self, TIn, ERROR>) -> bool + Clone>(
+        self,
+        predicate: TFn,
+        error: impl Into<String>,
+    ) -> VerifyParser<TIn, Self, TFn, ERROR> {
+        VerifyParser::new(self, predicate, error.into())
+    }
+
+    /// Fails expecting `expected` at the start of the input `self` consumed if `predicate`
+    /// rejects the output. Unlike the error of `verify`, the expected item is merged with those of
+    /// alternatives that fail at the same position.
+    fn filter<TFn: Fn(&ParserOut<'a, Self, TIn, ERROR>) -> bool + Clone>(
+        self,
+        predicate: TFn,
+        expected: impl Into<String>,
+    ) -> VerifyParser<TIn, Self, TFn, ERROR> {
+        VerifyParser::expecting(self, predicate, expected.into())
+    }
+
+    /// Continues with the parser `f` builds from the output of `self`.
+    fn and_then<
+        P2: RawTestParser<'a, TIn, ERROR>,
+        TFn: Fn(ParserOut<'a, Self, TIn, ERROR>) -> P2 + Clone,
+    >(
+        self,
+        f: TFn,
+    ) -> AndThenParser<TIn, Self, TFn, ERROR> {
+        AndThenParser::new(self, f)
+    }
+
+    fn skip<P2: RawTestParser<'a, TIn, ERROR, TOut = ()> + Clone>(
         self,
         other: P2,
     ) -> SkipParser<TIn, Self, P2, ERROR> {
@@ -54,10 +119,7 @@ where
         self.skip(EofParser::new())
     }
 
-    fn many(self) -> ManyParser<TIn, Self>
-    where
-        Self: RawTestParser<TIn, false>,
-    {
+    fn many(self) -> ManyParser<TIn, Self, ERROR> {
         ManyParser::new(self)
     }
 
@@ -65,21 +127,76 @@ where
         ManyNonEmptyParser::new(self)
     }
 
-    fn separated_by<P2: RawTestParser<TIn, ERROR, TOut = ()> + Clone>(
+    /// Parses `self` exactly `count` times.
+    fn repeat(self, count: usize) -> RepeatParser<TIn, Self, ERROR> {
+        RepeatParser::new(self, count..=count)
+    }
+
+    fn at_least(self, min: usize) -> RepeatParser<TIn, Self, ERROR> {
+        RepeatParser::new(self, min..)
+    }
+
+    fn at_most(self, max: usize) -> RepeatParser<TIn, Self, ERROR> {
+        RepeatParser::new(self, ..=max)
+    }
+
+    /// Parses `self` as often as possible within `range`, e.g. `2..=8`, and fails if it matched
+    /// fewer times than the range allows.
+    fn repeat_range(self, range: impl RangeBounds<usize>) -> RepeatParser<TIn, Self, ERROR> {
+        RepeatParser::new(self, range)
+    }
+
+    /// Like `repeat`, with the count fixed at compile time and the results in an array.
+    fn repeat_array<const N: usize>(self) -> RepeatArrayParser<TIn, Self, N, ERROR> {
+        RepeatArrayParser::new(self)
+    }
+
+    /// Like `many`, but folds the results into an accumulator instead of collecting them.
+    fn fold_many<TAcc: Clone, TFn: Fn(TAcc, ParserOut<'a, Self, TIn, ERROR>) -> TAcc + Clone>(
+        self,
+        initial: TAcc,
+        f: TFn,
+    ) -> FoldManyParser<TIn, Self, TAcc, TFn, ERROR> {
+        FoldManyParser::new(self, initial, f)
+    }
+
+    /// Parses `self (operator self)*` and folds the results from the left with the functions
+    /// returned by `operator`.
+    fn chain_left<P2: RawTestParser<'a, TIn, ERROR> + Clone>(
+        self,
+        operator: P2,
+    ) -> ChainLeftParser<TIn, Self, P2, ERROR> {
+        ChainLeftParser::new(self, operator)
+    }
+
+    /// Like `chain_left`, but folds from the right.
+    fn chain_right<P2: RawTestParser<'a, TIn, ERROR> + Clone>(
+        self,
+        operator: P2,
+    ) -> ChainRightParser<TIn, Self, P2, ERROR> {
+        ChainRightParser::new(self, operator)
+    }
+
+    fn separated_by<P2: RawTestParser<'a, TIn, ERROR, TOut = ()> + Clone>(
         self,
         separator: P2,
     ) -> SeparatedByParser<TIn, Self, P2, ERROR> {
         SeparatedByParser::new(self, separator)
     }
 
-    fn optional(self) -> OptionalParser<TIn, Self>
-    where
-        Self: RawTestParser<TIn, false>,
-    {
+    /// Like `separated_by`, but also returns the separators.
+    fn separated_by_keep<P2: RawTestParser<'a, TIn, ERROR> + Clone>(
+        self,
+        separator: P2,
+    ) -> SeparatedByKeepParser<TIn, Self, P2, ERROR> {
+        SeparatedByKeepParser::new(self, separator)
+    }
+
+    fn optional(self) -> OptionalParser<TIn, Self, ERROR> {
         OptionalParser::new(self)
     }
 
-    fn trim<P2: RawTestParser<TIn, ERROR, TOut = ()> + Clone>(
+    fn trim<P2: RawTestParser<'a, TIn, ERROR, TOut = ()> + Clone>(
         self,
         other: P2,
     ) -> SurroundParser<TIn, Self, P2, P2, ERROR> {
@@ -87,8 +204,8 @@ where
     }
 
     fn surround<
-        LParser: RawTestParser<TIn, ERROR, TOut = ()>,
-        RParser: RawTestParser<TIn, ERROR, TOut = ()>,
+        LParser: RawTestParser<'a, TIn, ERROR, TOut = ()>,
+        RParser: RawTestParser<'a, TIn, ERROR, TOut = ()>,
     >(
         self,
         l: LParser,
@@ -96,53 +213,475 @@ where
     ) -> SurroundParser<TIn, Self, LParser, RParser, ERROR> {
         SurroundParser::new(self, l, r)
     }
+
+    fn label(self, label: impl Into<String>) -> LabelParser<TIn, Self, ERROR> {
+        LabelParser::new(self, label.into())
+    }
+
+    /// Adds a frame to failures inside `self`, use `label` to name it when it fails at its start.
+    fn context(self, context: impl Into<String>) -> ContextParser<TIn, Self, ERROR> {
+        ContextParser::new(self, context.into())
+    }
+
+    /// Runs `self` without consuming any input, the position is rewound whether it succeeds or
+    /// fails.
+    fn peek(self) -> PeekParser<TIn, Self, ERROR> {
+        PeekParser::new(self)
+    }
+
+    /// Succeeds without consuming input if `self` would succeed here.
+    fn and_predicate(self) -> PredicateParser<TIn, Self, false, ERROR> {
+        PredicateParser::new(self)
+    }
+
+    /// Succeeds without consuming input if `self` would fail here. The expected item quotes the
+    /// input `self` matched, e.g. `not followed by "f"`, use `label` for a better name.
+    fn not(self) -> PredicateParser<TIn, Self, true, ERROR> {
+        PredicateParser::new(self)
+    }
+
+    fn cut(self) -> CutParser<TIn, Self, ERROR> {
+        CutParser::new(self)
+    }
+
+    fn recover_with<
+        P2: RawTestParser<'a, TIn, ERROR, TOut = ParserOut<'a, Self, TIn, ERROR>> + Clone,
+    >(
+        self,
+        recovery: P2,
+    ) -> RecoverWithParser<TIn, Self, P2, ERROR> {
+        RecoverWithParser::new(self, recovery)
+    }
+
+    /// Outputs the input slice `self` consumed, `&[u8]` for `Utf8` input. Wrap the grammar for
+    /// `Input<'a>` to keep the slice in the output of a `ParserWrapper`, see `InputLifetime`.
+    fn recognize(self) -> RecognizeParser<TIn, Self, ERROR> {
+        RecognizeParser::new(self)
+    }
+
+    /// Wraps the output in a `Spanned` with the range of input `self` consumed.
+    fn spanned(self) -> SpannedParser<TIn, Self, ERROR> {
+        SpannedParser::new(self)
+    }
+
+    /// Caches the result of this parser per position for the duration of a parse.
+    fn memoize(self) -> MemoizeParser<TIn, Self, ERROR> {
+        MemoizeParser::new(self)
+    }
 }
 
-impl<TIn: Parsable, TP: RawTestParser<TIn, ERROR> + Clone, const ERROR: bool>
-    ParserCombinator<TIn, ERROR> for TP
+impl<'a, TIn: Parsable, TP: RawTestParser<'a, TIn, ERROR> + Clone, const ERROR: bool>
+    ParserCombinator<'a, TIn, ERROR> for TP
 where
-    <Self as RawTestParser<TIn, ERROR>>::TOut: Clone,
+    <Self as RawTestParser<'a, TIn, ERROR>>::TOut: Clone,
 {
 }
 
-pub trait RawTestParser<TIn: Parsable, const ERROR: bool> {
+pub trait RawTestParser<'a, TIn: Parsable, const ERROR: bool> {
     type TOut;
-    fn parse(&self, input: &[TIn::T], position: &mut ParsingPosition) -> ParseResult<Self::TOut>;
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<Self::TOut>;
+}
+
+/// How the parsers and functions boxed by `ParserWrapper`, `RecParser` and `PrecedenceParser` are
+/// stored.
+///
+/// `Local` accepts any parser. `Shared` only accepts thread-safe ones and in turn makes the
+/// wrapper `Send + Sync`, so a grammar can be built once, stored in a `static` and used from
+/// several threads.
+pub trait Sharing {
+    type Parser<TIn: Parsable, TOut, const ERROR: bool>: ?Sized;
+    /// Like `Parser`, for parsers that only parse input of lifetime `'a`, see `InputLifetime`.
+    type ParserFor<'a, TIn: Parsable, TOut, const ERROR: bool>: ?Sized;
+    type Unary<T>: ?Sized + Fn(T) -> T;
+    type Binary<T>: ?Sized + Fn(T, T) -> T;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Local;
+
+impl Sharing for Local {
+    type Parser<TIn: Parsable, TOut, const ERROR: bool> =
+        dyn for<'a> RawTestParser<'a, TIn, ERROR, TOut = TOut>;
+    type ParserFor<'a, TIn: Parsable, TOut, const ERROR: bool> =
+        dyn RawTestParser<'a, TIn, ERROR, TOut = TOut> + 'a;
+    type Unary<T> = dyn Fn(T) -> T;
+    type Binary<T> = dyn Fn(T, T) -> T;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shared;
+
+impl Sharing for Shared {
+    type Parser<TIn: Parsable, TOut, const ERROR: bool> =
+        dyn for<'a> RawTestParser<'a, TIn, ERROR, TOut = TOut> + Send + Sync;
+    type ParserFor<'a, TIn: Parsable, TOut, const ERROR: bool> =
+        dyn RawTestParser<'a, TIn, ERROR, TOut = TOut> + Send + Sync + 'a;
+    type Unary<T> = dyn Fn(T) -> T + Send + Sync;
+    type Binary<T> = dyn Fn(T, T) -> T + Send + Sync;
+}
+
+/// Which input the parsers boxed by `ParserWrapper` and `RecParser` parse, independently of their
+/// `Sharing`.
+///
+/// `AnyInput` accepts parsers for input of any lifetime, so a wrapper built once parses owned
+/// input too. `Input<'a>` also accepts parsers whose output borrows from input that lives for
+/// `'a`, e.g. the slices of `recognize`, and only parses input of that lifetime.
+pub trait InputLifetime {
+    type Parser<S: Sharing, TIn: Parsable, TOut, const ERROR: bool>: ?Sized;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnyInput;
+
+impl InputLifetime for AnyInput {
+    type Parser<S: Sharing, TIn: Parsable, TOut, const ERROR: bool> = S::Parser<TIn, TOut, ERROR>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Input<'a>(std::marker::PhantomData<&'a ()>);
+
+impl<'a> InputLifetime for Input<'a> {
+    type Parser<S: Sharing, TIn: Parsable, TOut, const ERROR: bool> =
+        S::ParserFor<'a, TIn, TOut, ERROR>;
+}
+
+/// Boxes a value as the trait object `T` of `S`, implemented for the parsers and functions that
+/// `S` accepts.
+pub trait IntoBoxed<S: Sharing, T: ?Sized> {
+    fn into_boxed(self) -> Box<T>;
+}
+
+impl<TIn: Parsable, TOut, const ERROR: bool, P>
+    IntoBoxed<Local, dyn for<'a> RawTestParser<'a, TIn, ERROR, TOut = TOut>> for P
+where
+    P: for<'a> RawTestParser<'a, TIn, ERROR, TOut = TOut> + 'static,
+{
+    fn into_boxed(self) -> Box<dyn for<'a> RawTestParser<'a, TIn, ERROR, TOut = TOut>> {
+        Box::new(self)
+    }
+}
+
+impl<TIn: Parsable, TOut, const ERROR: bool, P>
+    IntoBoxed<Shared, dyn for<'a> RawTestParser<'a, TIn, ERROR, TOut = TOut> + Send + Sync> for P
+where
+    P: for<'a> RawTestParser<'a, TIn, ERROR, TOut = TOut> + Send + Sync + 'static,
+{
+    fn into_boxed(
+        self,
+    ) -> Box<dyn for<'a> RawTestParser<'a, TIn, ERROR, TOut = TOut> + Send + Sync> {
+        Box::new(self)
+    }
+}
+
+impl<T, F: Fn(T) -> T + 'static> IntoBoxed<Local, dyn Fn(T) -> T> for F {
+    fn into_boxed(self) -> Box<dyn Fn(T) -> T> {
+        Box::new(self)
+    }
 }
 
-pub struct ParserWrapper<TIn: Parsable, TOut: Clone, const ERROR: bool = false> {
-    parser: Box<dyn RawTestParser<TIn, ERROR, TOut = TOut>>,
+impl<T, F: Fn(T) -> T + Send + Sync + 'static> IntoBoxed<Shared, dyn Fn(T) -> T + Send + Sync>
+    for F
+{
+    fn into_boxed(self) -> Box<dyn Fn(T) -> T + Send + Sync> {
+        Box::new(self)
+    }
+}
+
+impl<T, F: Fn(T, T) -> T + 'static> IntoBoxed<Local, dyn Fn(T, T) -> T> for F {
+    fn into_boxed(self) -> Box<dyn Fn(T, T) -> T> {
+        Box::new(self)
+    }
+}
+
+impl<T, F: Fn(T, T) -> T + Send + Sync + 'static> IntoBoxed<Shared, dyn Fn(T, T) -> T + Send + Sync>
+    for F
+{
+    fn into_boxed(self) -> Box<dyn Fn(T, T) -> T + Send + Sync> {
+        Box::new(self)
+    }
+}
+
+pub struct ParserWrapper<
+    TIn: Parsable,
+    TOut: Clone,
+    const ERROR: bool = false,
+    S: Sharing = Local,
+    L: InputLifetime = AnyInput,
+> {
+    parser: Box<L::Parser<S, TIn, TOut, ERROR>>,
     __phantom1: std::marker::PhantomData<TIn>,
 }
 
 impl<TIn: Parsable, TOut: Clone, const ERROR: bool> ParserWrapper<TIn, TOut, ERROR> {
-    pub fn new(parser: Box<dyn RawTestParser<TIn, ERROR, TOut = TOut>>) -> Self {
+    pub fn new(parser: Box<dyn for<'a> RawTestParser<'a, TIn, ERROR, TOut = TOut>>) -> Self {
         Self {
             parser,
             __phantom1: std::marker::PhantomData,
         }
     }
 
-    pub fn from_parser<P: RawTestParser<TIn, ERROR, TOut = TOut> + 'static>(parser: P) -> Self {
+    pub fn from_parser<P: for<'a> RawTestParser<'a, TIn, ERROR, TOut = TOut> + 'static>(
+        parser: P,
+    ) -> Self {
+        Self::new(Box::new(parser))
+    }
+}
+
+impl<TIn: Parsable, TOut: Clone, const ERROR: bool> ParserWrapper<TIn, TOut, ERROR, Shared> {
+    pub fn new_sync(
+        parser: Box<dyn for<'a> RawTestParser<'a, TIn, ERROR, TOut = TOut> + Send + Sync>,
+    ) -> Self {
         Self {
-            parser: Box::new(parser),
+            parser,
             __phantom1: std::marker::PhantomData,
         }
     }
 
-    // pub fn parse(&self, data: TIn::List) -> ParseResult<TOut> {
-    //     let list = TIn::list_to_owned_slice(data);
-    //     self.parser.parse(&list, &mut ParsingPosition::default())
-    // }
+    pub fn from_parser_sync<
+        P: for<'a> RawTestParser<'a, TIn, ERROR, TOut = TOut> + Send + Sync + 'static,
+    >(
+        parser: P,
+    ) -> Self {
+        Self::new_sync(Box::new(parser))
+    }
+}
 
-    pub fn parse_slice<'s>(&self, slice: &'s [TIn::T]) -> ParseResult<TOut> {
-        self.parser.parse(&slice, &mut ParsingPosition::default())
+impl<'a, TIn: Parsable, TOut: Clone, const ERROR: bool>
+    ParserWrapper<TIn, TOut, ERROR, Local, Input<'a>>
+{
+    pub fn new_borrowed(parser: Box<dyn RawTestParser<'a, TIn, ERROR, TOut = TOut> + 'a>) -> Self {
+        Self {
+            parser,
+            __phantom1: std::marker::PhantomData,
+        }
+    }
+
+    /// Wraps a parser whose output borrows from the input, see `InputLifetime`.
+    pub fn from_parser_borrowed<P: RawTestParser<'a, TIn, ERROR, TOut = TOut> + 'a>(
+        parser: P,
+    ) -> Self {
+        Self::new_borrowed(Box::new(parser))
     }
 }
 
-// impl<TOut: Clone, P: Parser<'static, char, TOut>> ParserWrapper<'static, char, TOut, P> {
-//     pub fn parse_str(&self, s: &str) -> ParseResult<TOut> {
-//         let chars = s.chars().collect::<Vec<_>>();
-//         self.parse_slice(&chars)
-//     }
-// }
+impl<'a, TIn: Parsable, TOut: Clone, const ERROR: bool>
+    ParserWrapper<TIn, TOut, ERROR, Shared, Input<'a>>
+{
+    pub fn new_borrowed_sync(
+        parser: Box<dyn RawTestParser<'a, TIn, ERROR, TOut = TOut> + Send + Sync + 'a>,
+    ) -> Self {
+        Self {
+            parser,
+            __phantom1: std::marker::PhantomData,
+        }
+    }
+
+    pub fn from_parser_borrowed_sync<
+        P: RawTestParser<'a, TIn, ERROR, TOut = TOut> + Send + Sync + 'a,
+    >(
+        parser: P,
+    ) -> Self {
+        Self::new_borrowed_sync(Box::new(parser))
+    }
+}
+
+impl<TIn: Parsable, TOut: Clone, const ERROR: bool, S: Sharing, L: InputLifetime>
+    ParserWrapper<TIn, TOut, ERROR, S, L>
+{
+    pub fn parse_slice<'s>(&self, slice: &'s [TIn::T]) -> ParseResult<TOut>
+    where
+        L::Parser<S, TIn, TOut, ERROR>: RawTestParser<'s, TIn, ERROR, TOut = TOut>,
+    {
+        self.parser.parse(
+            &slice,
+            &mut ParsingPosition::default(),
+            &mut ParseContext::new(),
+        )
+    }
+
+    /// Parses as much of the slice as the parser accepts and returns the output together with the
+    /// position after it.
+    pub fn parse_prefix<'s>(&self, slice: &'s [TIn::T]) -> ParseResult<(TOut, ParsingPosition)>
+    where
+        L::Parser<S, TIn, TOut, ERROR>: RawTestParser<'s, TIn, ERROR, TOut = TOut>,
+    {
+        self.parse_prefix_in(slice, &mut ParseContext::new())
+    }
+
+    pub(crate) fn parse_prefix_in<'s>(
+        &self,
+        slice: &'s [TIn::T],
+        context: &mut ParseContext<'s>,
+    ) -> ParseResult<(TOut, ParsingPosition)>
+    where
+        L::Parser<S, TIn, TOut, ERROR>: RawTestParser<'s, TIn, ERROR, TOut = TOut>,
+    {
+        let mut position = ParsingPosition::default();
+        match self.parser.parse(slice, &mut position, context) {
+            ParseResult::Success(t) => ParseResult::Success((t, position)),
+            ParseResult::Failure(f) => ParseResult::Failure(f),
+        }
+    }
+
+    /// Like `parse_slice`, but fails if the parser does not consume the whole slice.
+    pub fn parse_all<'s>(&self, slice: &'s [TIn::T]) -> ParseResult<TOut>
+    where
+        L::Parser<S, TIn, TOut, ERROR>: RawTestParser<'s, TIn, ERROR, TOut = TOut>,
+    {
+        match self.parse_prefix(slice) {
+            ParseResult::Success((t, position)) if position.at_eof(slice) => {
+                ParseResult::Success(t)
+            }
+            ParseResult::Success((_, position)) => {
+                let span = ParsingRange::new(position, slice.len().into());
+                ParseResult::Failure(ParseFailure::with_error(
+                    span,
+                    "unexpected trailing input".to_string(),
+                ))
+            }
+            ParseResult::Failure(f) => ParseResult::Failure(f),
+        }
+    }
+
+    /// Parses the slice and returns the output, if any, together with every failure that was
+    /// recovered from and the final failure if parsing did not succeed.
+    pub fn parse_slice_recovering<'s>(
+        &self,
+        slice: &'s [TIn::T],
+    ) -> (Option<TOut>, Vec<ParseFailure>)
+    where
+        L::Parser<S, TIn, TOut, ERROR>: RawTestParser<'s, TIn, ERROR, TOut = TOut>,
+    {
+        let mut context = ParseContext::new();
+        let result = self
+            .parser
+            .parse(slice, &mut ParsingPosition::default(), &mut context);
+
+        let mut errors = context.take_diagnostics();
+        match result {
+            ParseResult::Success(t) => (Some(t), errors),
+            ParseResult::Failure(f) => {
+                errors.push(f);
+                (None, errors)
+            }
+        }
+    }
+}
+
+/// Parsing owned input needs a parser that accepts input of any lifetime.
+impl<TIn: DecodeParsable, TOut: Clone, const ERROR: bool, S: Sharing>
+    ParserWrapper<TIn, TOut, ERROR, S>
+where
+    S::Parser<TIn, TOut, ERROR>: for<'s> RawTestParser<'s, TIn, ERROR, TOut = TOut>,
+{
+    pub fn parse_str(&self, input: impl AsRef<str>) -> Parsed<TIn, TOut> {
+        let input = TIn::decode_str(input.as_ref());
+        let result = self.parse_slice(&input);
+        Parsed { input, result }
+    }
+
+    /// Fails if `TIn` is text and the bytes are not valid UTF-8.
+    pub fn parse_bytes(&self, input: &[u8]) -> Result<Parsed<TIn, TOut>, Utf8Error> {
+        let input = TIn::decode_bytes(input.to_vec())?;
+        let result = self.parse_slice(&input);
+        Ok(Parsed { input, result })
+    }
+
+    /// Reads all of `reader` before parsing, invalid UTF-8 is reported as
+    /// `io::ErrorKind::InvalidData`.
+    pub fn parse_reader(&self, mut reader: impl Read) -> io::Result<Parsed<TIn, TOut>> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        let input =
+            TIn::decode_bytes(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let result = self.parse_slice(&input);
+        Ok(Parsed { input, result })
+    }
+
+    pub fn parse_file(&self, path: impl AsRef<Path>) -> io::Result<Parsed<TIn, TOut>> {
+        self.parse_reader(File::open(path)?)
+    }
+}
+
+/// The result of parsing owned input, kept together with the input to render failures.
+pub struct Parsed<TIn: Parsable, TOut> {
+    pub input: Box<[TIn::T]>,
+    pub result: ParseResult<TOut>,
+}
+
+impl<TIn: Parsable, TOut> Parsed<TIn, TOut> {
+    /// The report for the failure, if parsing failed.
+    pub fn report(&self) -> Option<FailureReport<'_, TIn>> {
+        match &self.result {
+            ParseResult::Success(_) => None,
+            ParseResult::Failure(f) => Some(f.report::<TIn>(&self.input)),
+        }
+    }
+
+    pub fn into_result(self) -> ParseResult<TOut> {
+        self.result
+    }
+}
+
+/// A grammar written once for both values of `ERROR`, see `DualParserWrapper`. Implement
+/// `Grammar<TIn, Shared>` for a grammar that is parsed from several threads.
+pub trait Grammar<TIn: Parsable, S: Sharing = Local> {
+    type TOut: Clone;
+
+    fn define<const ERROR: bool>(&self) -> ParserWrapper<TIn, Self::TOut, ERROR, S>;
+}
+
+/// Parses with the fast `ERROR = false` instantiation of a grammar and only re-parses with the
+/// `ERROR = true` one to describe the failure when the fast one fails.
+pub struct DualParserWrapper<TIn: Parsable, TOut: Clone, S: Sharing = Local> {
+    fast: ParserWrapper<TIn, TOut, false, S>,
+    detailed: ParserWrapper<TIn, TOut, true, S>,
+}
+
+impl<TIn: Parsable, TOut: Clone, S: Sharing> DualParserWrapper<TIn, TOut, S> {
+    pub fn from_grammar<G: Grammar<TIn, S, TOut = TOut>>(grammar: &G) -> Self {
+        DualParserWrapper {
+            fast: grammar.define::<false>(),
+            detailed: grammar.define::<true>(),
+        }
+    }
+
+    pub fn new(
+        fast: ParserWrapper<TIn, TOut, false, S>,
+        detailed: ParserWrapper<TIn, TOut, true, S>,
+    ) -> Self {
+        DualParserWrapper { fast, detailed }
+    }
+
+    pub fn parse_slice<'s>(&self, slice: &'s [TIn::T]) -> ParseResult<TOut>
+    where
+        S::Parser<TIn, TOut, false>: RawTestParser<'s, TIn, false, TOut = TOut>,
+        S::Parser<TIn, TOut, true>: RawTestParser<'s, TIn, true, TOut = TOut>,
+    {
+        match self.fast.parse_slice(slice) {
+            ParseResult::Success(t) => ParseResult::Success(t),
+            ParseResult::Failure(_) => self.detailed.parse_slice(slice),
+        }
+    }
+
+    /// Like `ParserWrapper::parse_slice_recovering`, the detailed parser runs if the fast one
+    /// reported any failure.
+    pub fn parse_slice_recovering<'s>(
+        &self,
+        slice: &'s [TIn::T],
+    ) -> (Option<TOut>, Vec<ParseFailure>)
+    where
+        S::Parser<TIn, TOut, false>: RawTestParser<'s, TIn, false, TOut = TOut>,
+        S::Parser<TIn, TOut, true>: RawTestParser<'s, TIn, true, TOut = TOut>,
+    {
+        match self.fast.parse_slice_recovering(slice) {
+            (Some(t), errors) if errors.is_empty() => (Some(t), errors),
+            _ => self.detailed.parse_slice_recovering(slice),
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use crate::{
+    ParseResult, ParsingPosition,
+    context::ParseContext,
+    parsable::Parsable,
+    parser::{ParserOut, RawTestParser},
+};
+
+// ---------------
+// Recover with parser
+// ---------------
+
+#[derive(Clone)]
+pub struct RecoverWithParser<TIn: Parsable, P, PR, const ERROR: bool> {
+    parser: P,
+    recovery: PR,
+    __phantom1: std::marker::PhantomData<TIn>,
+}
+
+impl<TIn: Parsable, P, PR, const ERROR: bool> RecoverWithParser<TIn, P, PR, ERROR> {
+    pub fn new(parser: P, recovery: PR) -> Self {
+        RecoverWithParser {
+            parser,
+            recovery,
+            __phantom1: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<
+    'a,
+    TIn: Parsable,
+    P: RawTestParser<'a, TIn, ERROR>,
+    PR: RawTestParser<'a, TIn, ERROR, TOut = ParserOut<'a, P, TIn, ERROR>>,
+    const ERROR: bool,
+> RawTestParser<'a, TIn, ERROR> for RecoverWithParser<TIn, P, PR, ERROR>
+{
+    type TOut = ParserOut<'a, P, TIn, ERROR>;
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<Self::TOut> {
+        let start = position.clone();
+        let diagnostics = context.diagnostics_len();
+
+        let failure = match self.parser.parse(input, position, context) {
+            ParseResult::Success(t) => return ParseResult::Success(t),
+            // running out of buffer is not an error, the input is resumed once more arrives
+            ParseResult::Failure(f) if f.incomplete().is_some() => return ParseResult::Failure(f),
+            ParseResult::Failure(f) => f,
+        };
+
+        // the failure supersedes anything the inner parser recovered from before it failed
+        context.truncate_diagnostics(diagnostics);
+        position.advance_to(start);
+
+        match self.recovery.parse(input, position, context) {
+            ParseResult::Success(placeholder) => {
+                context.push_diagnostic(failure);
+                ParseResult::Success(placeholder)
+            }
+            ParseResult::Failure(_) => ParseResult::Failure(failure),
+        }
+    }
+}
+
+// ---------------
+// Skip until parser
+// ---------------
+
+#[derive(Clone)]
+pub struct SkipUntilParser<TIn: Parsable> {
+    terminator: TIn::T,
+}
+
+impl<'a, TIn: Parsable, const ERROR: bool> RawTestParser<'a, TIn, ERROR> for SkipUntilParser<TIn> {
+    type TOut = ();
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<()> {
+        match position
+            .slice(input)
+            .iter()
+            .position(|t| *t == self.terminator)
+        {
+            Some(offset) => position.succeed_offset(offset + 1, ()),
+            None => {
+                if let Some(incomplete) = context.incomplete_at(input, input.len(), 1) {
+                    return incomplete;
+                }
+
+                let error = if ERROR {
+                    Some(vec![TIn::t_to_string(&self.terminator)])
+                } else {
+                    None
+                };
+                position.fail_at(input.len().into(), error)
+            }
+        }
+    }
+}
+
+/// Skips everything up to and including the next `terminator`.
+pub fn skip_until<TIn: Parsable>(terminator: TIn::T) -> SkipUntilParser<TIn> {
+    SkipUntilParser { terminator }
+}
+
+// ---------------
+// Recover nested parser
+// ---------------
+
+#[derive(Clone)]
+pub struct RecoverNestedParser<TIn: Parsable> {
+    open: TIn::T,
+    close: TIn::T,
+}
+
+impl<'a, TIn: Parsable, const ERROR: bool> RawTestParser<'a, TIn, ERROR>
+    for RecoverNestedParser<TIn>
+{
+    type TOut = ();
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<()> {
+        if let Some(incomplete) = context.incomplete_at(input, position.index(), 1) {
+            return incomplete;
+        }
+
+        if !position.current_eq(input, &self.open) {
+            let error = if ERROR {
+                Some(vec![TIn::t_to_string(&self.open)])
+            } else {
+                None
+            };
+            return position.fail_offset(0, error);
+        }
+
+        let mut depth = 0_usize;
+        for (offset, t) in position.slice(input).iter().enumerate() {
+            if *t == self.open {
+                depth += 1;
+            } else if *t == self.close {
+                depth -= 1;
+                if depth == 0 {
+                    return position.succeed_offset(offset + 1, ());
+                }
+            }
+        }
+
+        if let Some(incomplete) = context.incomplete_at(input, input.len(), depth) {
+            return incomplete;
+        }
+
+        let error = if ERROR {
+            Some(vec![TIn::t_to_string(&self.close)])
+        } else {
+            None
+        };
+        position.fail_at(input.len().into(), error)
+    }
+}
+
+/// Skips a balanced `open` ... `close` block, including nested blocks.
+pub fn recover_nested<TIn: Parsable>(open: TIn::T, close: TIn::T) -> RecoverNestedParser<TIn> {
+    RecoverNestedParser { open, close }
+}
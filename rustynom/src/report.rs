@@ -0,0 +1,119 @@
+use std::fmt::{Display, Formatter, Result};
+
+use crate::{ParseFailure, location::LineIndex, parsable::Parsable};
+
+const RESET: &str = "\x1b[0m";
+const RED_BOLD: &str = "\x1b[1;31m";
+const BLUE_BOLD: &str = "\x1b[1;34m";
+
+impl ParseFailure {
+    /// The expected items, sorted and without duplicates.
+    pub fn expected_items(&self) -> Vec<&str> {
+        let mut items = self
+            .expected
+            .iter()
+            .flatten()
+            .map(|s| s.as_str())
+            .collect::<Vec<_>>();
+        items.sort_unstable();
+        items.dedup();
+        items
+    }
+
+    pub fn message(&self) -> String {
+        let items = self.expected_items();
+        let message = match (self.error(), items.len()) {
+            (Some(error), _) => error.to_string(),
+            (None, 0) => "parsing failed".to_string(),
+            (None, 1) => format!("expected {}", items[0]),
+            (None, _) => format!("expected one of: {}", items.join(", ")),
+        };
+
+        if self.context().is_empty() {
+            message
+        } else {
+            format!("{} {}", message, self.context().join(" "))
+        }
+    }
+
+    pub fn report<'a, TIn: Parsable>(&'a self, input: &'a [TIn::T]) -> FailureReport<'a, TIn> {
+        FailureReport {
+            failure: self,
+            input,
+            colored: false,
+        }
+    }
+}
+
+impl Display for ParseFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{} at index {}", self.message(), self.furthest.index())
+    }
+}
+
+/// Renders a `ParseFailure` together with the source line it points into.
+pub struct FailureReport<'a, TIn: Parsable> {
+    failure: &'a ParseFailure,
+    input: &'a [TIn::T],
+    colored: bool,
+}
+
+impl<TIn: Parsable> FailureReport<'_, TIn> {
+    pub fn colored(mut self, colored: bool) -> Self {
+        self.colored = colored;
+        self
+    }
+
+    fn paint(&self, f: &mut Formatter<'_>, color: &str, text: &str) -> Result {
+        if self.colored {
+            write!(f, "{}{}{}", color, text, RESET)
+        } else {
+            write!(f, "{}", text)
+        }
+    }
+}
+
+impl<TIn: Parsable> Display for FailureReport<'_, TIn> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let line_index = LineIndex::new::<TIn>(self.input);
+        let location = self.failure.line_column(&line_index);
+        let line_range = line_index.line_range(location.line);
+
+        let line = line_range.slice(self.input);
+        // render whole runs of items so that multi-byte text is decoded as one
+        let render = |items: &[TIn::T]| TIn::list_to_string(&TIn::slice_to_list(items));
+        let width = |items: &[TIn::T]| render(items).chars().count();
+        let rendered_line = render(line);
+        let rendered_line = rendered_line.strip_suffix('\r').unwrap_or(&rendered_line);
+        // the caret goes where the item at the position starts, after any separator before it
+        let offset = self.failure.furthest.index().min(self.input.len()) - line_range.from.index();
+        let caret_offset = match line.get(offset) {
+            Some(_) => width(&line[..=offset]) - width(&line[offset..=offset]),
+            None => width(line),
+        };
+        // underline the rejected input, as far as it is on this line
+        let caret_len = match self.failure.span() {
+            Some(span) => {
+                let end = span.to.index().min(line_range.to.index());
+                let start = self.failure.furthest.index().min(end);
+                width(&self.input[start..end]).max(1)
+            }
+            None => 1,
+        };
+
+        let line_number = location.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+
+        self.paint(f, RED_BOLD, "error")?;
+        writeln!(f, ": {}", self.failure.message())?;
+        self.paint(f, BLUE_BOLD, &format!("{}-->", gutter))?;
+        writeln!(f, " {}", location)?;
+        self.paint(f, BLUE_BOLD, &format!("{} |", gutter))?;
+        writeln!(f)?;
+        self.paint(f, BLUE_BOLD, &format!("{} |", line_number))?;
+        writeln!(f, " {}", rendered_line)?;
+        self.paint(f, BLUE_BOLD, &format!("{} |", gutter))?;
+        write!(f, " {}", " ".repeat(caret_offset))?;
+        self.paint(f, RED_BOLD, &"^".repeat(caret_len))
+    }
+}
@@ -0,0 +1,219 @@
+use std::{
+    fmt::{Display, Formatter},
+    io::{self, Read},
+};
+
+use crate::{
+    ParseFailure, ParseResult, ParsingPosition, ParsingRange,
+    context::ParseContext,
+    parsable::Parsable,
+    parser::{Local, ParserWrapper, RawTestParser, Sharing},
+};
+
+const CHUNK_SIZE: usize = 8 * 1024;
+
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    /// A failure with its positions relative to the start of the stream.
+    Parse(ParseFailure),
+}
+
+impl Display for StreamError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StreamError::Io(e) => write!(f, "{}", e),
+            StreamError::Parse(failure) => write!(f, "{}", failure),
+        }
+    }
+}
+
+impl std::error::Error for StreamError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StreamError::Io(e) => Some(e),
+            StreamError::Parse(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for StreamError {
+    fn from(e: io::Error) -> Self {
+        StreamError::Io(e)
+    }
+}
+
+/// Runs a parser repeatedly over input that arrives in chunks, e.g. the messages of a line
+/// oriented protocol, and yields one output per parsed item.
+///
+/// The parser runs in partial mode, see `ParseContext::is_partial`: parsers that run into the end
+/// of the buffer fail with `ParseFailure::incomplete` and the item is parsed again from its start
+/// once more chunks are appended. Each time the buffered part of the item at least doubles, so an
+/// item spanning many chunks is only parsed a logarithmic number of times, at the cost of reading
+/// ahead up to the length of the item. After the last chunk the rest of the buffer is parsed as
+/// complete input. Iteration stops after the first error.
+pub struct StreamParser<'p, TIn: Parsable, TOut: Clone, const ERROR: bool, S: Sharing = Local> {
+    parser: &'p ParserWrapper<TIn, TOut, ERROR, S>,
+    chunks: Box<dyn Iterator<Item = io::Result<Vec<TIn::T>>> + 'p>,
+    buffer: Vec<TIn::T>,
+    /// The number of items at the start of `buffer` that have been parsed, they are dropped when
+    /// the next chunk is appended.
+    consumed: usize,
+    /// The index of `buffer[consumed]` in the stream.
+    offset: usize,
+    /// Set once `chunks` is exhausted.
+    finished: bool,
+    /// Set after an error.
+    done: bool,
+}
+
+impl<'p, TIn: Parsable, TOut: Clone, const ERROR: bool, S: Sharing>
+    StreamParser<'p, TIn, TOut, ERROR, S>
+{
+    pub fn new(
+        parser: &'p ParserWrapper<TIn, TOut, ERROR, S>,
+        chunks: impl IntoIterator<Item = Vec<TIn::T>, IntoIter: 'p>,
+    ) -> Self {
+        StreamParser::from_results(parser, chunks.into_iter().map(Ok))
+    }
+
+    fn from_results(
+        parser: &'p ParserWrapper<TIn, TOut, ERROR, S>,
+        chunks: impl Iterator<Item = io::Result<Vec<TIn::T>>> + 'p,
+    ) -> Self {
+        StreamParser {
+            parser,
+            chunks: Box::new(chunks),
+            buffer: Vec::new(),
+            consumed: 0,
+            offset: 0,
+            finished: false,
+            done: false,
+        }
+    }
+
+    /// The index in the stream of the first item that has not been parsed yet.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The items of the buffer that have not been parsed yet.
+    fn pending(&self) -> &[TIn::T] {
+        &self.buffer[self.consumed..]
+    }
+
+    /// Appends chunks to the buffer until it holds at least `target` pending items, or sets
+    /// `finished` if there are not enough.
+    fn fill(&mut self, target: usize) -> io::Result<()> {
+        self.buffer.drain(..self.consumed);
+        self.consumed = 0;
+        while !self.finished && self.buffer.len() < target {
+            match self.chunks.next() {
+                Some(chunk) => self.buffer.extend(chunk?),
+                None => self.finished = true,
+            }
+        }
+        Ok(())
+    }
+
+    fn shift(&self, position: &ParsingPosition) -> ParsingPosition {
+        ParsingPosition::new(position.index() + self.offset)
+    }
+
+    fn fail(&mut self, mut failure: ParseFailure) -> Option<Result<TOut, StreamError>> {
+        self.done = true;
+        failure.furthest = self.shift(&failure.furthest);
+        if let Some(span) = failure.span_mut() {
+            *span = ParsingRange::new(self.shift(&span.from), self.shift(&span.to));
+        }
+        Some(Err(StreamError::Parse(failure)))
+    }
+}
+
+impl<'p, TIn: Parsable<T = u8>, TOut: Clone, const ERROR: bool, S: Sharing>
+    StreamParser<'p, TIn, TOut, ERROR, S>
+{
+    /// Reads the input from `reader` in chunks of 8 KiB.
+    pub fn from_reader(
+        parser: &'p ParserWrapper<TIn, TOut, ERROR, S>,
+        mut reader: impl Read + 'p,
+    ) -> Self {
+        let chunks = std::iter::from_fn(move || {
+            let mut chunk = vec![0; CHUNK_SIZE];
+            loop {
+                match reader.read(&mut chunk) {
+                    Ok(0) => return None,
+                    Ok(read) => {
+                        chunk.truncate(read);
+                        return Some(Ok(chunk));
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => return Some(Err(e)),
+                }
+            }
+        });
+
+        StreamParser::from_results(parser, chunks)
+    }
+}
+
+impl<TIn: Parsable, TOut: Clone, const ERROR: bool, S: Sharing> Iterator
+    for StreamParser<'_, TIn, TOut, ERROR, S>
+where
+    S::Parser<TIn, TOut, ERROR>: for<'a> RawTestParser<'a, TIn, ERROR, TOut = TOut>,
+{
+    type Item = Result<TOut, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        loop {
+            // an empty buffer is only parsed once it is known that nothing follows
+            if self.pending().is_empty() && !self.finished {
+                if let Err(e) = self.fill(1) {
+                    self.done = true;
+                    return Some(Err(StreamError::Io(e)));
+                }
+                continue;
+            }
+            if self.pending().is_empty() {
+                return None;
+            }
+
+            let result = {
+                let mut context = ParseContext::new();
+                context.set_partial(!self.finished);
+                self.parser.parse_prefix_in(self.pending(), &mut context)
+            };
+            match result {
+                ParseResult::Success((_, position)) if position.index() == 0 => {
+                    return self.fail(ParseFailure {
+                        committed: true,
+                        ..ParseFailure::with_error(
+                            ParsingRange::new(position.clone(), position),
+                            "stream parser succeeded without consuming input".to_string(),
+                        )
+                    });
+                }
+                ParseResult::Success((t, position)) => {
+                    self.consumed += position.index();
+                    self.offset += position.index();
+                    return Some(Ok(t));
+                }
+                ParseResult::Failure(f) => {
+                    let Some(needed) = f.incomplete() else {
+                        return self.fail(f);
+                    };
+                    // at least double the pending items before the item is parsed again
+                    let pending = self.pending().len();
+                    if let Err(e) = self.fill(pending + needed.max(pending)) {
+                        self.done = true;
+                        return Some(Err(StreamError::Io(e)));
+                    }
+                }
+            }
+        }
+    }
+}
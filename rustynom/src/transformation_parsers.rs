@@ -2,64 +2,197 @@
 // Recursive parser
 // ---------------
 
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    fmt::Display,
+    ops::{Bound, RangeBounds},
+    sync::{
+        Arc, OnceLock,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
 
 use crate::{
-    ParseResult, ParsingPosition,
+    ParseFailure, ParseResult, ParsingPosition, ParsingRange, Spanned,
+    context::{ErasedBox, MemoEntry, ParseContext},
     parsable::Parsable,
-    parser::{ParserOut, RawTestParser},
+    parser::{AnyInput, Input, InputLifetime, Local, ParserOut, RawTestParser, Shared, Sharing},
 };
 
-pub struct RecRefParser<TIn: Parsable, TOut: Clone, const ERROR: bool> {
-    parser_ref: Rc<RefCell<Option<Box<dyn RawTestParser<TIn, ERROR, TOut = TOut>>>>>,
+static NEXT_REC_ID: AtomicUsize = AtomicUsize::new(0);
+
+type BoxedParser<TIn, TOut, const ERROR: bool, S, L> =
+    Box<<L as InputLifetime>::Parser<S, TIn, TOut, ERROR>>;
+
+pub struct RecRefParser<
+    TIn: Parsable,
+    TOut: Clone,
+    const ERROR: bool,
+    S: Sharing = Local,
+    L: InputLifetime = AnyInput,
+> {
+    parser_ref: Arc<OnceLock<BoxedParser<TIn, TOut, ERROR, S, L>>>,
+    /// Shared by clones, identifies the seeds of this parser's left recursive calls in the
+    /// recursion frames of the context.
+    id: usize,
 }
 
-impl<TIn: Parsable, TOut: Clone, const ERROR: bool> Clone for RecRefParser<TIn, TOut, ERROR> {
+impl<TIn: Parsable, TOut: Clone, const ERROR: bool, S: Sharing, L: InputLifetime> Clone
+    for RecRefParser<TIn, TOut, ERROR, S, L>
+{
     fn clone(&self) -> Self {
         RecRefParser {
             parser_ref: self.parser_ref.clone(),
+            id: self.id,
         }
     }
 }
 
 impl<TIn: Parsable, TOut: Clone, const ERROR: bool> RecRefParser<TIn, TOut, ERROR> {
     pub fn new() -> Self {
+        RecRefParser::empty()
+    }
+}
+
+impl<TIn: Parsable, TOut: Clone, const ERROR: bool> RecRefParser<TIn, TOut, ERROR, Shared> {
+    /// A reference to a `Send + Sync` parser, see `Sharing`.
+    pub fn new_sync() -> Self {
+        RecRefParser::empty()
+    }
+}
+
+impl<'a, TIn: Parsable, TOut: Clone, const ERROR: bool>
+    RecRefParser<TIn, TOut, ERROR, Local, Input<'a>>
+{
+    /// A reference to a parser whose output borrows from the input, see `InputLifetime`.
+    pub fn new_borrowed() -> Self {
+        RecRefParser::empty()
+    }
+}
+
+impl<'a, TIn: Parsable, TOut: Clone, const ERROR: bool>
+    RecRefParser<TIn, TOut, ERROR, Shared, Input<'a>>
+{
+    pub fn new_borrowed_sync() -> Self {
+        RecRefParser::empty()
+    }
+}
+
+impl<TIn: Parsable, TOut: Clone, const ERROR: bool, S: Sharing, L: InputLifetime>
+    RecRefParser<TIn, TOut, ERROR, S, L>
+{
+    fn empty() -> Self {
         RecRefParser {
-            parser_ref: Rc::new(RefCell::new(None)),
+            parser_ref: Arc::new(OnceLock::new()),
+            id: NEXT_REC_ID.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+
+    /// Sets the parser this reference points to, this can only be done once.
+    pub fn set(&self, parser: Box<L::Parser<S, TIn, TOut, ERROR>>) {
+        if self.parser_ref.set(parser).is_err() {
+            panic!("RecRefParser already has a parser set");
         }
     }
 
-    pub fn set(&self, parser: Box<dyn RawTestParser<TIn, ERROR, TOut = TOut>>) {
-        self.parser_ref.borrow_mut().replace(parser);
+    fn parse_inner<'a>(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<TOut>
+    where
+        L::Parser<S, TIn, TOut, ERROR>: RawTestParser<'a, TIn, ERROR, TOut = TOut>,
+    {
+        self.parser_ref
+            .get()
+            .expect("RecRefParser has no parser set")
+            .parse(input, position, context)
     }
 }
 
-impl<TIn: Parsable, TOut: Clone, const ERROR: bool> RawTestParser<TIn, ERROR>
-    for RecRefParser<TIn, TOut, ERROR>
+impl<'a, TIn: Parsable, TOut: Clone, const ERROR: bool, S: Sharing, L: InputLifetime>
+    RawTestParser<'a, TIn, ERROR> for RecRefParser<TIn, TOut, ERROR, S, L>
+where
+    L::Parser<S, TIn, TOut, ERROR>: RawTestParser<'a, TIn, ERROR, TOut = TOut>,
+    TOut: 'a,
 {
     type TOut
         = TOut
     where
         TIn::T:;
 
-    fn parse(&self, input: &[TIn::T], position: &mut ParsingPosition) -> ParseResult<TOut> {
-        self.parser_ref
-            .borrow()
-            .as_ref()
-            .expect("RecRefParser has no parser set")
-            .parse(input, position)
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<TOut> {
+        let start = position.clone();
+
+        // a call at the same position is left recursion, it gets the seed instead of recursing
+        if let Some(frame) = context.recursion_frame(self.id, start.index()) {
+            frame.left_recursive = true;
+            return match &frame.seed {
+                Some((seed, end)) => {
+                    // SAFETY: the seeds of frames with `self.id` are only set below, by clones of
+                    // this parser
+                    let result = unsafe { seed.downcast_ref::<ParseResult<TOut>>() }.clone();
+                    position.advance_to(end.clone());
+                    result
+                }
+                None => position.fail_offset(0, None),
+            };
+        }
+
+        context.push_recursion_frame(self.id, start.index());
+        let diagnostics = context.diagnostics_len();
+        let mut result = self.parse_inner(input, position, context);
+
+        // grow the seed until reparsing with it as the left recursive result stops consuming more
+        if context.top_recursion_frame().left_recursive {
+            while result.is_success() {
+                let end = position.clone();
+                let seed_diagnostics = context.diagnostics()[diagnostics..].to_vec();
+                context.top_recursion_frame().seed =
+                    Some((ErasedBox::new(result.clone()), end.clone()));
+                context.invalidate_memo_at(start.index());
+
+                context.truncate_diagnostics(diagnostics);
+                position.advance_to(start.clone());
+                let grown = self.parse_inner(input, position, context);
+
+                if grown.is_failure() || *position <= end {
+                    context.truncate_diagnostics(diagnostics);
+                    for diagnostic in seed_diagnostics {
+                        context.push_diagnostic(diagnostic);
+                    }
+                    position.advance_to(end);
+                    break;
+                }
+                result = grown;
+            }
+        }
+
+        context.pop_recursion_frame();
+        result
     }
 }
 
 #[derive(Clone)]
-pub struct RecParser<TIn: Parsable, TOut: Clone, const ERROR: bool> {
-    parser: RecRefParser<TIn, TOut, ERROR>,
+pub struct RecParser<
+    TIn: Parsable,
+    TOut: Clone,
+    const ERROR: bool,
+    S: Sharing = Local,
+    L: InputLifetime = AnyInput,
+> {
+    parser: RecRefParser<TIn, TOut, ERROR, S, L>,
 }
 
 impl<TIn: Parsable, TOut: Clone, const ERROR: bool> RecParser<TIn, TOut, ERROR> {
     pub fn new<TP>(decl: impl FnOnce(RecRefParser<TIn, TOut, ERROR>) -> TP) -> Self
     where
-        TP: RawTestParser<TIn, ERROR, TOut = TOut> + 'static,
+        TP: for<'a> RawTestParser<'a, TIn, ERROR, TOut = TOut> + 'static,
     {
         let rec_ref = RecRefParser::new();
 
@@ -71,27 +204,86 @@ impl<TIn: Parsable, TOut: Clone, const ERROR: bool> RecParser<TIn, TOut, ERROR>
     }
 }
 
-impl<TIn: Parsable, TOut: Clone, const ERROR: bool> RawTestParser<TIn, ERROR>
-    for RecParser<TIn, TOut, ERROR>
+impl<TIn: Parsable, TOut: Clone, const ERROR: bool> RecParser<TIn, TOut, ERROR, Shared> {
+    /// Like `new` for a `Send + Sync` grammar, see `Sharing`.
+    pub fn new_sync<TP>(decl: impl FnOnce(RecRefParser<TIn, TOut, ERROR, Shared>) -> TP) -> Self
+    where
+        TP: for<'a> RawTestParser<'a, TIn, ERROR, TOut = TOut> + Send + Sync + 'static,
+    {
+        let rec_ref = RecRefParser::new_sync();
+
+        let parser = decl(rec_ref.clone());
+
+        rec_ref.set(Box::from(parser));
+
+        RecParser { parser: rec_ref }
+    }
+}
+
+impl<'a, TIn: Parsable, TOut: Clone, const ERROR: bool>
+    RecParser<TIn, TOut, ERROR, Local, Input<'a>>
+{
+    /// Like `new` for a grammar whose output borrows from the input, see `InputLifetime`.
+    pub fn new_borrowed<TP>(
+        decl: impl FnOnce(RecRefParser<TIn, TOut, ERROR, Local, Input<'a>>) -> TP,
+    ) -> Self
+    where
+        TP: RawTestParser<'a, TIn, ERROR, TOut = TOut> + 'a,
+    {
+        let rec_ref = RecRefParser::new_borrowed();
+
+        let parser = decl(rec_ref.clone());
+
+        rec_ref.set(Box::from(parser));
+
+        RecParser { parser: rec_ref }
+    }
+}
+
+impl<'a, TIn: Parsable, TOut: Clone, const ERROR: bool>
+    RecParser<TIn, TOut, ERROR, Shared, Input<'a>>
+{
+    /// Like `new_borrowed` for a `Send + Sync` grammar.
+    pub fn new_borrowed_sync<TP>(
+        decl: impl FnOnce(RecRefParser<TIn, TOut, ERROR, Shared, Input<'a>>) -> TP,
+    ) -> Self
+    where
+        TP: RawTestParser<'a, TIn, ERROR, TOut = TOut> + Send + Sync + 'a,
+    {
+        let rec_ref = RecRefParser::new_borrowed_sync();
+
+        let parser = decl(rec_ref.clone());
+
+        rec_ref.set(Box::from(parser));
+
+        RecParser { parser: rec_ref }
+    }
+}
+
+impl<'a, TIn: Parsable, TOut: Clone, const ERROR: bool, S: Sharing, L: InputLifetime>
+    RawTestParser<'a, TIn, ERROR> for RecParser<TIn, TOut, ERROR, S, L>
+where
+    L::Parser<S, TIn, TOut, ERROR>: RawTestParser<'a, TIn, ERROR, TOut = TOut>,
+    TOut: 'a,
 {
     type TOut
         = TOut
     where
         TIn::T:;
 
-    fn parse(&self, input: &[TIn::T], position: &mut ParsingPosition) -> ParseResult<TOut> {
-        self.parser.parse(input, position)
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<TOut> {
+        self.parser.parse(input, position, context)
     }
 }
 
 #[derive(Clone)]
-pub struct MapParser<
-    TIn: Parsable,
-    TOut: Clone,
-    TP: RawTestParser<TIn, ERROR>,
-    TFn: (Fn(<TP as RawTestParser<TIn, ERROR>>::TOut) -> TOut),
-    const ERROR: bool,
-> where
+pub struct MapParser<TIn: Parsable, TOut: Clone, TP, TFn, const ERROR: bool>
+where
     TIn::T:,
 {
     parser: TP,
@@ -100,14 +292,7 @@ pub struct MapParser<
     __phantom2: std::marker::PhantomData<TOut>,
 }
 
-impl<
-    TIn: Parsable,
-    TOut: Clone,
-    TP: RawTestParser<TIn, ERROR>,
-    TFn: (Fn(<TP as RawTestParser<TIn, ERROR>>::TOut) -> TOut),
-    const ERROR: bool,
-> MapParser<TIn, TOut, TP, TFn, ERROR>
-{
+impl<TIn: Parsable, TOut: Clone, TP, TFn, const ERROR: bool> MapParser<TIn, TOut, TP, TFn, ERROR> {
     pub fn new(parser: TP, f: TFn) -> Self {
         MapParser {
             parser,
@@ -119,37 +304,225 @@ impl<
 }
 
 impl<
+    'a,
     TIn: Parsable,
     TOut: Clone,
-    TP: RawTestParser<TIn, ERROR>,
-    TFn: (Fn(<TP as RawTestParser<TIn, ERROR>>::TOut) -> TOut),
+    TP: RawTestParser<'a, TIn, ERROR>,
+    TFn: (Fn(<TP as RawTestParser<'a, TIn, ERROR>>::TOut) -> TOut),
     const ERROR: bool,
-> RawTestParser<TIn, ERROR> for MapParser<TIn, TOut, TP, TFn, ERROR>
+> RawTestParser<'a, TIn, ERROR> for MapParser<TIn, TOut, TP, TFn, ERROR>
 {
     type TOut
         = TOut
     where
         TIn::T:;
 
-    fn parse(&self, input: &[TIn::T], position: &mut ParsingPosition) -> ParseResult<TOut> {
-        match self.parser.parse(input, position) {
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<TOut> {
+        match self.parser.parse(input, position, context) {
             ParseResult::Success(x) => ParseResult::Success((self.f)(x)),
             ParseResult::Failure(f) => ParseResult::Failure(f),
         }
     }
 }
 
+// ---------------
+// Fallible map parsers
+// ---------------
+
+#[derive(Clone)]
+pub struct TryMapParser<TIn: Parsable, TOut: Clone, TErr, TP, TFn, const ERROR: bool> {
+    parser: TP,
+    f: TFn,
+    __phantom1: std::marker::PhantomData<TIn>,
+    __phantom2: std::marker::PhantomData<(TOut, TErr)>,
+}
+
+impl<TIn: Parsable, TOut: Clone, TErr, TP, TFn, const ERROR: bool>
+    TryMapParser<TIn, TOut, TErr, TP, TFn, ERROR>
+{
+    pub fn new(parser: TP, f: TFn) -> Self {
+        TryMapParser {
+            parser,
+            f,
+            __phantom1: std::marker::PhantomData,
+            __phantom2: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<
+    'a,
+    TIn: Parsable,
+    TOut: Clone,
+    TErr: Display,
+    TP: RawTestParser<'a, TIn, ERROR>,
+    TFn: Fn(ParserOut<'a, TP, TIn, ERROR>) -> Result<TOut, TErr>,
+    const ERROR: bool,
+> RawTestParser<'a, TIn, ERROR> for TryMapParser<TIn, TOut, TErr, TP, TFn, ERROR>
+{
+    type TOut = TOut;
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<TOut> {
+        let start = position.clone();
+        let x = match self.parser.parse(input, position, context) {
+            ParseResult::Success(x) => x,
+            ParseResult::Failure(f) => return ParseResult::Failure(f),
+        };
+
+        match (self.f)(x) {
+            Ok(t) => ParseResult::Success(t),
+            Err(e) if ERROR => {
+                let span = ParsingRange::new(start.clone(), position.clone());
+                position.advance_to(start);
+                ParseResult::Failure(ParseFailure::with_error(span, e.to_string()))
+            }
+            Err(_) => position.fail_at(start, None),
+        }
+    }
+}
+
+/// How `VerifyParser` describes a rejected output.
+#[derive(Clone)]
+enum Rejection {
+    Error(String),
+    Expected(String),
+}
+
+#[derive(Clone)]
+pub struct VerifyParser<TIn: Parsable, TP, TFn, const ERROR: bool> {
+    parser: TP,
+    predicate: TFn,
+    rejection: Rejection,
+    __phantom1: std::marker::PhantomData<TIn>,
+}
+
+impl<TIn: Parsable, TP, TFn, const ERROR: bool> VerifyParser<TIn, TP, TFn, ERROR> {
+    pub fn new(parser: TP, predicate: TFn, error: String) -> Self {
+        VerifyParser {
+            parser,
+            predicate,
+            rejection: Rejection::Error(error),
+            __phantom1: std::marker::PhantomData,
+        }
+    }
+
+    pub fn expecting(parser: TP, predicate: TFn, expected: String) -> Self {
+        VerifyParser {
+            parser,
+            predicate,
+            rejection: Rejection::Expected(expected),
+            __phantom1: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<
+    'a,
+    TIn: Parsable,
+    TP: RawTestParser<'a, TIn, ERROR>,
+    TFn: Fn(&ParserOut<'a, TP, TIn, ERROR>) -> bool,
+    const ERROR: bool,
+> RawTestParser<'a, TIn, ERROR> for VerifyParser<TIn, TP, TFn, ERROR>
+{
+    type TOut = ParserOut<'a, TP, TIn, ERROR>;
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<Self::TOut> {
+        let start = position.clone();
+        let x = match self.parser.parse(input, position, context) {
+            ParseResult::Success(x) => x,
+            ParseResult::Failure(f) => return ParseResult::Failure(f),
+        };
+
+        if (self.predicate)(&x) {
+            return ParseResult::Success(x);
+        }
+
+        if !ERROR {
+            return position.fail_at(start, None);
+        }
+
+        let span = ParsingRange::new(start.clone(), position.clone());
+        position.advance_to(start);
+        ParseResult::Failure(match &self.rejection {
+            Rejection::Error(error) => ParseFailure::with_error(span, error.clone()),
+            Rejection::Expected(expected) => {
+                ParseFailure::with_expected(span, vec![expected.clone()])
+            }
+        })
+    }
+}
+
+// ---------------
+// And then parser
+// ---------------
+
+#[derive(Clone)]
+pub struct AndThenParser<TIn: Parsable, TP, TFn, const ERROR: bool> {
+    parser: TP,
+    f: TFn,
+    __phantom1: std::marker::PhantomData<TIn>,
+}
+
+impl<TIn: Parsable, TP, TFn, const ERROR: bool> AndThenParser<TIn, TP, TFn, ERROR> {
+    pub fn new(parser: TP, f: TFn) -> Self {
+        AndThenParser {
+            parser,
+            f,
+            __phantom1: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<
+    'a,
+    TIn: Parsable,
+    TP: RawTestParser<'a, TIn, ERROR>,
+    TP2: RawTestParser<'a, TIn, ERROR>,
+    TFn: Fn(ParserOut<'a, TP, TIn, ERROR>) -> TP2,
+    const ERROR: bool,
+> RawTestParser<'a, TIn, ERROR> for AndThenParser<TIn, TP, TFn, ERROR>
+{
+    type TOut = ParserOut<'a, TP2, TIn, ERROR>;
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<Self::TOut> {
+        match self.parser.parse(input, position, context) {
+            ParseResult::Success(x) => (self.f)(x).parse(input, position, context),
+            ParseResult::Failure(f) => ParseResult::Failure(f),
+        }
+    }
+}
+
 // ---------------
 // Many parser
 // ---------------
 
 #[derive(Clone)]
-pub struct ManyParser<TIn: Parsable, TP: RawTestParser<TIn, false>> {
+pub struct ManyParser<TIn: Parsable, TP, const ERROR: bool> {
     parser: TP,
     __phantom1: std::marker::PhantomData<TIn>,
 }
 
-impl<TIn: Parsable, TP: RawTestParser<TIn, false>> ManyParser<TIn, TP> {
+impl<TIn: Parsable, TP, const ERROR: bool> ManyParser<TIn, TP, ERROR> {
     pub fn new(parser: TP) -> Self {
         ManyParser {
             parser,
@@ -158,20 +531,36 @@ impl<TIn: Parsable, TP: RawTestParser<TIn, false>> ManyParser<TIn, TP> {
     }
 }
 
-impl<TIn: Parsable, TP: RawTestParser<TIn, false>, const ERROR: bool> RawTestParser<TIn, ERROR>
-    for ManyParser<TIn, TP>
+impl<'a, TIn: Parsable, TP: RawTestParser<'a, TIn, ERROR>, const ERROR: bool>
+    RawTestParser<'a, TIn, ERROR> for ManyParser<TIn, TP, ERROR>
 {
     type TOut
-        = Vec<ParserOut<TP, TIn, false>>
+        = Vec<ParserOut<'a, TP, TIn, ERROR>>
     where
         TIn::T:;
 
-    fn parse(&self, input: &[TIn::T], position: &mut ParsingPosition) -> ParseResult<Self::TOut> {
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<Self::TOut> {
         let mut result = Vec::new();
-        while let ParseResult::Success(t) = self.parser.parse(input, position) {
-            result.push(t);
+        loop {
+            let start = position.clone();
+            let diagnostics = context.diagnostics_len();
+            match self.parser.parse(input, position, context) {
+                ParseResult::Success(_) if *position == start => {
+                    return ParseResult::Failure(ParseFailure::no_progress::<TP>(start));
+                }
+                ParseResult::Success(t) => result.push(t),
+                ParseResult::Failure(f) if f.committed => return ParseResult::Failure(f),
+                ParseResult::Failure(_) => {
+                    context.truncate_diagnostics(diagnostics);
+                    return position.succeed_at(start, result);
+                }
+            }
         }
-        position.succeed_offset(0, result)
     }
 }
 
@@ -180,14 +569,12 @@ impl<TIn: Parsable, TP: RawTestParser<TIn, false>, const ERROR: bool> RawTestPar
 // ---------------
 
 #[derive(Clone)]
-pub struct ManyNonEmptyParser<TIn: Parsable, TP: RawTestParser<TIn, ERROR>, const ERROR: bool> {
+pub struct ManyNonEmptyParser<TIn: Parsable, TP, const ERROR: bool> {
     parser: TP,
     __phantom1: std::marker::PhantomData<TIn>,
 }
 
-impl<TIn: Parsable, TP: RawTestParser<TIn, ERROR>, const ERROR: bool>
-    ManyNonEmptyParser<TIn, TP, ERROR>
-{
+impl<TIn: Parsable, TP, const ERROR: bool> ManyNonEmptyParser<TIn, TP, ERROR> {
     pub fn new(parser: TP) -> Self {
         ManyNonEmptyParser {
             parser,
@@ -196,26 +583,229 @@ impl<TIn: Parsable, TP: RawTestParser<TIn, ERROR>, const ERROR: bool>
     }
 }
 
-impl<TIn: Parsable, TP: RawTestParser<TIn, ERROR>, const ERROR: bool> RawTestParser<TIn, ERROR>
-    for ManyNonEmptyParser<TIn, TP, ERROR>
+impl<'a, TIn: Parsable, TP: RawTestParser<'a, TIn, ERROR>, const ERROR: bool>
+    RawTestParser<'a, TIn, ERROR> for ManyNonEmptyParser<TIn, TP, ERROR>
 {
     type TOut
-        = Vec<ParserOut<TP, TIn, ERROR>>
+        = Vec<ParserOut<'a, TP, TIn, ERROR>>
     where
         TIn::T:;
 
-    fn parse(&self, input: &[TIn::T], position: &mut ParsingPosition) -> ParseResult<Self::TOut> {
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<Self::TOut> {
         let mut result = Vec::new();
 
-        match self.parser.parse(input, position) {
+        match self.parser.parse(input, position, context) {
             ParseResult::Success(t) => result.push(t),
             ParseResult::Failure(f) => return ParseResult::Failure(f),
         }
 
-        while let ParseResult::Success(t) = self.parser.parse(input, position) {
-            result.push(t);
+        loop {
+            let start = position.clone();
+            let diagnostics = context.diagnostics_len();
+            match self.parser.parse(input, position, context) {
+                ParseResult::Success(_) if *position == start => {
+                    return ParseResult::Failure(ParseFailure::no_progress::<TP>(start));
+                }
+                ParseResult::Success(t) => result.push(t),
+                ParseResult::Failure(f) if f.committed => return ParseResult::Failure(f),
+                ParseResult::Failure(_) => {
+                    context.truncate_diagnostics(diagnostics);
+                    return position.succeed_at(start, result);
+                }
+            }
+        }
+    }
+}
+
+// ---------------
+// Fold many parser
+// ---------------
+
+#[derive(Clone)]
+pub struct FoldManyParser<TIn: Parsable, TP, TAcc, TFn, const ERROR: bool> {
+    parser: TP,
+    initial: TAcc,
+    f: TFn,
+    __phantom1: std::marker::PhantomData<TIn>,
+}
+
+impl<TIn: Parsable, TP, TAcc, TFn, const ERROR: bool> FoldManyParser<TIn, TP, TAcc, TFn, ERROR> {
+    pub fn new(parser: TP, initial: TAcc, f: TFn) -> Self {
+        FoldManyParser {
+            parser,
+            initial,
+            f,
+            __phantom1: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<
+    'a,
+    TIn: Parsable,
+    TP: RawTestParser<'a, TIn, ERROR>,
+    TAcc: Clone,
+    TFn: Fn(TAcc, ParserOut<'a, TP, TIn, ERROR>) -> TAcc,
+    const ERROR: bool,
+> RawTestParser<'a, TIn, ERROR> for FoldManyParser<TIn, TP, TAcc, TFn, ERROR>
+{
+    type TOut = TAcc;
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<TAcc> {
+        let mut acc = self.initial.clone();
+        loop {
+            let cloned_position = position.clone();
+            let diagnostics = context.diagnostics_len();
+            match self.parser.parse(input, position, context) {
+                ParseResult::Success(_) if *position == cloned_position => {
+                    return ParseResult::Failure(ParseFailure::no_progress::<TP>(cloned_position));
+                }
+                ParseResult::Success(t) => acc = (self.f)(acc, t),
+                ParseResult::Failure(f) if f.committed => return ParseResult::Failure(f),
+                ParseResult::Failure(_) => {
+                    context.truncate_diagnostics(diagnostics);
+                    return position.succeed_at(cloned_position, acc);
+                }
+            }
+        }
+    }
+}
+
+// ---------------
+// Repeat parser
+// ---------------
+
+#[derive(Clone)]
+pub struct RepeatParser<TIn: Parsable, TP, const ERROR: bool> {
+    parser: TP,
+    min: usize,
+    max: Option<usize>,
+    __phantom1: std::marker::PhantomData<TIn>,
+}
+
+impl<TIn: Parsable, TP, const ERROR: bool> RepeatParser<TIn, TP, ERROR> {
+    pub fn new(parser: TP, range: impl RangeBounds<usize>) -> Self {
+        let min = match range.start_bound() {
+            Bound::Included(start) => *start,
+            Bound::Excluded(start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let max = match range.end_bound() {
+            Bound::Included(end) => Some(*end),
+            Bound::Excluded(end) => Some(end.checked_sub(1).expect("empty repeat range")),
+            Bound::Unbounded => None,
+        };
+        assert!(max.is_none_or(|max| min <= max), "empty repeat range");
+
+        RepeatParser {
+            parser,
+            min,
+            max,
+            __phantom1: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<'a, TIn: Parsable, TP: RawTestParser<'a, TIn, ERROR>, const ERROR: bool>
+    RawTestParser<'a, TIn, ERROR> for RepeatParser<TIn, TP, ERROR>
+{
+    type TOut
+        = Vec<ParserOut<'a, TP, TIn, ERROR>>
+    where
+        TIn::T:;
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<Self::TOut> {
+        // every item consumes input, so no more than the rest of the input can fit
+        let mut result = Vec::with_capacity(self.min.min(input.len() - position.index()));
+        let mut failure = None;
+        while self.max.is_none_or(|max| result.len() < max) {
+            let start = position.clone();
+            let diagnostics = context.diagnostics_len();
+            match self.parser.parse(input, position, context) {
+                ParseResult::Success(_) if *position == start => {
+                    return ParseResult::Failure(ParseFailure::no_progress::<TP>(start));
+                }
+                ParseResult::Success(t) => result.push(t),
+                ParseResult::Failure(f) if f.committed => return ParseResult::Failure(f),
+                ParseResult::Failure(f) => {
+                    context.truncate_diagnostics(diagnostics);
+                    position.advance_to(start);
+                    failure = Some(f);
+                    break;
+                }
+            }
+        }
+
+        if result.len() >= self.min {
+            return ParseResult::Success(result);
+        }
+
+        let error = if ERROR {
+            let items = if self.min == 1 { "item" } else { "items" };
+            if self.max == Some(self.min) {
+                Some(vec![format!("{} {}", self.min, items)])
+            } else {
+                Some(vec![format!("at least {} {}", self.min, items)])
+            }
+        } else {
+            None
+        };
+        // the loop only ends below the minimum on a failure of the parser
+        let failure = failure.expect("repeat ended early without a failure");
+        let count = ParseFailure::new(position.clone(), error);
+        ParseResult::Failure(position.merge_failures(failure, count))
+    }
+}
+
+#[derive(Clone)]
+pub struct RepeatArrayParser<TIn: Parsable, TP, const N: usize, const ERROR: bool> {
+    parser: RepeatParser<TIn, TP, ERROR>,
+}
+
+impl<TIn: Parsable, TP, const N: usize, const ERROR: bool> RepeatArrayParser<TIn, TP, N, ERROR> {
+    pub fn new(parser: TP) -> Self {
+        RepeatArrayParser {
+            parser: RepeatParser::new(parser, N..=N),
+        }
+    }
+}
+
+impl<'a, TIn: Parsable, TP: RawTestParser<'a, TIn, ERROR>, const N: usize, const ERROR: bool>
+    RawTestParser<'a, TIn, ERROR> for RepeatArrayParser<TIn, TP, N, ERROR>
+{
+    type TOut
+        = [ParserOut<'a, TP, TIn, ERROR>; N]
+    where
+        TIn::T:;
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<Self::TOut> {
+        match self.parser.parse(input, position, context) {
+            ParseResult::Success(items) => match items.try_into() {
+                Ok(array) => ParseResult::Success(array),
+                Err(_) => unreachable!("repeat parser returned the wrong number of items"),
+            },
+            ParseResult::Failure(f) => ParseResult::Failure(f),
         }
-        position.succeed_offset(0, result)
     }
 }
 
@@ -224,12 +814,12 @@ impl<TIn: Parsable, TP: RawTestParser<TIn, ERROR>, const ERROR: bool> RawTestPar
 // ---------------
 
 #[derive(Clone)]
-pub struct OptionalParser<TIn: Parsable, TP: RawTestParser<TIn, false>> {
+pub struct OptionalParser<TIn: Parsable, TP, const ERROR: bool> {
     parser: TP,
     __phantom1: std::marker::PhantomData<TIn>,
 }
 
-impl<TIn: Parsable, TP: RawTestParser<TIn, false>> OptionalParser<TIn, TP> {
+impl<TIn: Parsable, TP, const ERROR: bool> OptionalParser<TIn, TP, ERROR> {
     pub fn new(parser: TP) -> Self {
         OptionalParser {
             parser,
@@ -238,18 +828,367 @@ impl<TIn: Parsable, TP: RawTestParser<TIn, false>> OptionalParser<TIn, TP> {
     }
 }
 
-impl<TIn: Parsable, TP: RawTestParser<TIn, false>, const ERROR: bool> RawTestParser<TIn, ERROR>
-    for OptionalParser<TIn, TP>
+impl<'a, TIn: Parsable, TP: RawTestParser<'a, TIn, ERROR>, const ERROR: bool>
+    RawTestParser<'a, TIn, ERROR> for OptionalParser<TIn, TP, ERROR>
 {
     type TOut
-        = Option<ParserOut<TP, TIn, false>>
+        = Option<ParserOut<'a, TP, TIn, ERROR>>
     where
         TIn::T:;
 
-    fn parse(&self, input: &[TIn::T], position: &mut ParsingPosition) -> ParseResult<Self::TOut> {
-        match self.parser.parse(input, position) {
-            ParseResult::Success(t) => position.succeed_offset(0, Some(t)),
-            ParseResult::Failure(_) => position.succeed_offset(0, None),
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<Self::TOut> {
+        let start = position.clone();
+        let diagnostics = context.diagnostics_len();
+        match self.parser.parse(input, position, context) {
+            ParseResult::Success(t) => ParseResult::Success(Some(t)),
+            ParseResult::Failure(f) if f.committed => ParseResult::Failure(f),
+            ParseResult::Failure(_) => {
+                context.truncate_diagnostics(diagnostics);
+                position.succeed_at(start, None)
+            }
+        }
+    }
+}
+
+// ---------------
+// Lookahead parsers
+// ---------------
+
+#[derive(Clone)]
+pub struct PeekParser<TIn: Parsable, TP, const ERROR: bool> {
+    parser: TP,
+    __phantom1: std::marker::PhantomData<TIn>,
+}
+
+impl<TIn: Parsable, TP, const ERROR: bool> PeekParser<TIn, TP, ERROR> {
+    pub fn new(parser: TP) -> Self {
+        PeekParser {
+            parser,
+            __phantom1: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<'a, TIn: Parsable, TP: RawTestParser<'a, TIn, ERROR>, const ERROR: bool>
+    RawTestParser<'a, TIn, ERROR> for PeekParser<TIn, TP, ERROR>
+{
+    type TOut = ParserOut<'a, TP, TIn, ERROR>;
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<Self::TOut> {
+        let start = position.clone();
+        let diagnostics = context.diagnostics_len();
+        let result = self.parser.parse(input, position, context);
+        if result.is_success() {
+            context.truncate_diagnostics(diagnostics);
+        }
+        position.advance_to(start);
+        result
+    }
+}
+
+/// The PEG predicates `&e` and, with `NEGATE`, `!e`. Neither consumes input.
+#[derive(Clone)]
+pub struct PredicateParser<TIn: Parsable, TP, const NEGATE: bool, const ERROR: bool> {
+    parser: TP,
+    __phantom1: std::marker::PhantomData<TIn>,
+}
+
+impl<TIn: Parsable, TP, const NEGATE: bool, const ERROR: bool>
+    PredicateParser<TIn, TP, NEGATE, ERROR>
+{
+    pub fn new(parser: TP) -> Self {
+        PredicateParser {
+            parser,
+            __phantom1: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<'a, TIn: Parsable, TP: RawTestParser<'a, TIn, ERROR>, const NEGATE: bool, const ERROR: bool>
+    RawTestParser<'a, TIn, ERROR> for PredicateParser<TIn, TP, NEGATE, ERROR>
+{
+    type TOut = ();
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<()> {
+        let start = position.clone();
+        let diagnostics = context.diagnostics_len();
+        let result = self.parser.parse(input, position, context);
+        context.truncate_diagnostics(diagnostics);
+
+        match result {
+            ParseResult::Failure(f) if f.committed => ParseResult::Failure(f),
+            ParseResult::Success(_) if !NEGATE => position.succeed_at(start, ()),
+            ParseResult::Failure(f) if !NEGATE => ParseResult::Failure(f),
+            ParseResult::Failure(_) => position.succeed_at(start, ()),
+            ParseResult::Success(_) => {
+                let error = if ERROR {
+                    let matched = TIn::slice_to_list(&input[start.index()..position.index()]);
+                    Some(vec![format!(
+                        "not followed by {:?}",
+                        TIn::list_to_string(&matched)
+                    )])
+                } else {
+                    None
+                };
+                position.fail_at(start, error)
+            }
+        }
+    }
+}
+
+// ---------------
+// Label parser
+// ---------------
+
+#[derive(Clone)]
+pub struct LabelParser<TIn: Parsable, TP, const ERROR: bool> {
+    parser: TP,
+    label: String,
+    __phantom1: std::marker::PhantomData<TIn>,
+}
+
+impl<TIn: Parsable, TP, const ERROR: bool> LabelParser<TIn, TP, ERROR> {
+    pub fn new(parser: TP, label: String) -> Self {
+        LabelParser {
+            parser,
+            label,
+            __phantom1: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<'a, TIn: Parsable, TP: RawTestParser<'a, TIn, ERROR>, const ERROR: bool>
+    RawTestParser<'a, TIn, ERROR> for LabelParser<TIn, TP, ERROR>
+{
+    type TOut = ParserOut<'a, TP, TIn, ERROR>;
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<Self::TOut> {
+        if !ERROR {
+            return self.parser.parse(input, position, context);
+        }
+
+        let start = position.clone();
+        match self.parser.parse(input, position, context) {
+            // only replace the expected items if the inner parser failed without consuming input,
+            // otherwise the inner failure is more precise than the label
+            ParseResult::Failure(mut f) if f.furthest == start => {
+                f.expected = Some(vec![self.label.clone()]);
+                ParseResult::Failure(f)
+            }
+            result => result,
+        }
+    }
+}
+
+// ---------------
+// Context parser
+// ---------------
+
+#[derive(Clone)]
+pub struct ContextParser<TIn: Parsable, TP, const ERROR: bool> {
+    parser: TP,
+    context: String,
+    __phantom1: std::marker::PhantomData<TIn>,
+}
+
+impl<TIn: Parsable, TP, const ERROR: bool> ContextParser<TIn, TP, ERROR> {
+    pub fn new(parser: TP, context: String) -> Self {
+        ContextParser {
+            parser,
+            context,
+            __phantom1: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<'a, TIn: Parsable, TP: RawTestParser<'a, TIn, ERROR>, const ERROR: bool>
+    RawTestParser<'a, TIn, ERROR> for ContextParser<TIn, TP, ERROR>
+{
+    type TOut = ParserOut<'a, TP, TIn, ERROR>;
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<Self::TOut> {
+        let start = position.clone();
+        match self.parser.parse(input, position, context) {
+            // a failure at the start that rejected nothing is not inside this construct, e.g. one
+            // alternative of several, and the frame would be merged into the others' failures
+            ParseResult::Failure(mut f) if ERROR && (f.furthest > start || f.error().is_some()) => {
+                f.push_context(self.context.clone());
+                ParseResult::Failure(f)
+            }
+            result => result,
+        }
+    }
+}
+
+// ---------------
+// Recognize parser
+// ---------------
+
+#[derive(Clone)]
+pub struct RecognizeParser<TIn: Parsable, TP, const ERROR: bool> {
+    parser: TP,
+    __phantom1: std::marker::PhantomData<TIn>,
+}
+
+impl<TIn: Parsable, TP, const ERROR: bool> RecognizeParser<TIn, TP, ERROR> {
+    pub fn new(parser: TP) -> Self {
+        RecognizeParser {
+            parser,
+            __phantom1: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<'a, TIn: Parsable, TP: RawTestParser<'a, TIn, ERROR>, const ERROR: bool>
+    RawTestParser<'a, TIn, ERROR> for RecognizeParser<TIn, TP, ERROR>
+where
+    TIn::T: 'a,
+{
+    type TOut = &'a [TIn::T];
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<Self::TOut> {
+        let start = position.clone();
+        match self.parser.parse(input, position, context) {
+            ParseResult::Success(_) => {
+                ParseResult::Success(start.slice_to(input, position.index()))
+            }
+            ParseResult::Failure(f) => ParseResult::Failure(f),
+        }
+    }
+}
+
+// ---------------
+// Spanned parser
+// ---------------
+
+#[derive(Clone)]
+pub struct SpannedParser<TIn: Parsable, TP, const ERROR: bool> {
+    parser: TP,
+    __phantom1: std::marker::PhantomData<TIn>,
+}
+
+impl<TIn: Parsable, TP, const ERROR: bool> SpannedParser<TIn, TP, ERROR> {
+    pub fn new(parser: TP) -> Self {
+        SpannedParser {
+            parser,
+            __phantom1: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<'a, TIn: Parsable, TP: RawTestParser<'a, TIn, ERROR>, const ERROR: bool>
+    RawTestParser<'a, TIn, ERROR> for SpannedParser<TIn, TP, ERROR>
+{
+    type TOut = Spanned<ParserOut<'a, TP, TIn, ERROR>>;
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<Self::TOut> {
+        let start = position.clone();
+        match self.parser.parse(input, position, context) {
+            ParseResult::Success(value) => ParseResult::Success(Spanned::new(
+                value,
+                ParsingRange::new(start, position.clone()),
+            )),
+            ParseResult::Failure(f) => ParseResult::Failure(f),
+        }
+    }
+}
+
+// ---------------
+// Memoize parser
+// ---------------
+
+static NEXT_MEMO_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Clones share the id of the parser they were cloned from, and with it their memo entries.
+#[derive(Clone)]
+pub struct MemoizeParser<TIn: Parsable, TP, const ERROR: bool> {
+    parser: TP,
+    id: usize,
+    __phantom1: std::marker::PhantomData<TIn>,
+}
+
+impl<TIn: Parsable, TP, const ERROR: bool> MemoizeParser<TIn, TP, ERROR> {
+    pub fn new(parser: TP) -> Self {
+        MemoizeParser {
+            parser,
+            id: NEXT_MEMO_ID.fetch_add(1, Ordering::Relaxed),
+            __phantom1: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<'a, TIn: Parsable, TP: RawTestParser<'a, TIn, ERROR>, const ERROR: bool>
+    RawTestParser<'a, TIn, ERROR> for MemoizeParser<TIn, TP, ERROR>
+where
+    ParserOut<'a, TP, TIn, ERROR>: Clone + 'a,
+{
+    type TOut = ParserOut<'a, TP, TIn, ERROR>;
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<Self::TOut> {
+        if let Some(entry) = context.memo_get(self.id, position) {
+            // SAFETY: the entries under `self.id` are only written below, by clones of this
+            // parser, and the context only holds entries for input of lifetime `'a`
+            let result = unsafe { entry.result.downcast_ref::<ParseResult<Self::TOut>>() }.clone();
+            let end = entry.end.clone();
+            for diagnostic in entry.diagnostics.clone() {
+                context.push_diagnostic(diagnostic);
+            }
+
+            position.advance_to(end);
+            return result;
         }
+
+        let start = position.clone();
+        let diagnostics = context.diagnostics_len();
+        let result = self.parser.parse(input, position, context);
+
+        let entry = MemoEntry {
+            result: ErasedBox::new(result.clone()),
+            end: position.clone(),
+            diagnostics: context.diagnostics()[diagnostics..].to_vec(),
+        };
+        context.memo_insert(self.id, &start, entry);
+
+        result
     }
 }
@@ -1,15 +1,22 @@
-use crate::{ParseResult, ParsingPosition, parsable::Parsable, parser::RawTestParser};
+use crate::{
+    ParseFailure, ParseResult, ParsingPosition, ParsingRange,
+    context::ParseContext,
+    number_parsers::{self, FloatFormat, IntFormat, IntScan},
+    parsable::{Parsable, TextParsable},
+    parser::RawTestParser,
+};
 
 #[derive(Clone)]
 pub struct PositionParser;
 
-impl<TIn: Parsable, const ERROR: bool> RawTestParser<TIn, ERROR> for PositionParser {
+impl<'a, TIn: Parsable, const ERROR: bool> RawTestParser<'a, TIn, ERROR> for PositionParser {
     type TOut = ParsingPosition;
 
     fn parse(
         &self,
-        _input: &[TIn::T],
+        _input: &'a [TIn::T],
         position: &mut ParsingPosition,
+        _context: &mut ParseContext<'a>,
     ) -> ParseResult<ParsingPosition> {
         ParseResult::Success(position.clone())
     }
@@ -22,16 +29,25 @@ pub fn position<TIn: Parsable>() -> PositionParser {
 #[derive(Clone)]
 pub struct AnyParser;
 
-impl<TIn: Parsable, const ERROR: bool> RawTestParser<TIn, ERROR> for AnyParser {
+impl<'a, TIn: Parsable, const ERROR: bool> RawTestParser<'a, TIn, ERROR> for AnyParser {
     type TOut = TIn::T;
 
-    fn parse(&self, input: &[TIn::T], position: &mut ParsingPosition) -> ParseResult<TIn::T> {
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<TIn::T> {
         let error = if ERROR {
             Some(vec!["any character".to_string()])
         } else {
             None
         };
 
+        if let Some(incomplete) = context.incomplete_at(input, position.index(), 1) {
+            return incomplete;
+        }
+
         if position.at_eof(input) {
             position.fail_offset(0, error)
         } else {
@@ -47,10 +63,20 @@ pub fn any<TIn: Parsable>() -> AnyParser {
 #[derive(Clone)]
 pub struct RemainingParser;
 
-impl<TIn: Parsable, const ERROR: bool> RawTestParser<TIn, ERROR> for RemainingParser {
+impl<'a, TIn: Parsable, const ERROR: bool> RawTestParser<'a, TIn, ERROR> for RemainingParser {
     type TOut = TIn::List;
 
-    fn parse(&self, input: &[TIn::T], position: &mut ParsingPosition) -> ParseResult<TIn::List> {
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<TIn::List> {
+        // everything up to the end of the input, which is not known yet in partial mode
+        if let Some(incomplete) = context.incomplete_at(input, input.len(), 1) {
+            return incomplete;
+        }
+
         let slice = position.slice(input);
         let len = slice.len();
         let remaining = TIn::slice_to_list(slice);
@@ -62,6 +88,35 @@ pub fn remaining<TIn: Parsable>() -> RemainingParser {
     RemainingParser
 }
 
+#[derive(Clone)]
+pub struct RemainingSliceParser;
+
+impl<'a, TIn: Parsable, const ERROR: bool> RawTestParser<'a, TIn, ERROR> for RemainingSliceParser
+where
+    TIn::T: 'a,
+{
+    type TOut = &'a [TIn::T];
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<&'a [TIn::T]> {
+        // everything up to the end of the input, which is not known yet in partial mode
+        if let Some(incomplete) = context.incomplete_at(input, input.len(), 1) {
+            return incomplete;
+        }
+
+        let slice = position.slice(input);
+        position.succeed_offset(slice.len(), slice)
+    }
+}
+
+pub fn remaining_slice<TIn: Parsable>() -> RemainingSliceParser {
+    RemainingSliceParser
+}
+
 #[derive(Clone)]
 pub struct TestParser<TIn: Parsable, TFn: Fn(&TIn::T) -> bool> {
     test_fn: TFn,
@@ -69,18 +124,27 @@ pub struct TestParser<TIn: Parsable, TFn: Fn(&TIn::T) -> bool> {
     __phantom: std::marker::PhantomData<TIn>,
 }
 
-impl<TIn: Parsable, TFn: Fn(&TIn::T) -> bool, const ERROR: bool> RawTestParser<TIn, ERROR>
+impl<'a, TIn: Parsable, TFn: Fn(&TIn::T) -> bool, const ERROR: bool> RawTestParser<'a, TIn, ERROR>
     for TestParser<TIn, TFn>
 {
     type TOut = TIn::T;
 
-    fn parse(&self, input: &[TIn::T], position: &mut ParsingPosition) -> ParseResult<TIn::T> {
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<TIn::T> {
         let error = if ERROR {
             Some(vec![self.error_str.clone()])
         } else {
             None
         };
 
+        if let Some(incomplete) = context.incomplete_at(input, position.index(), 1) {
+            return incomplete;
+        }
+
         if position.test_current(input, &self.test_fn) {
             position.succeed_offset(1, position.current(input).clone())
         } else {
@@ -107,12 +171,17 @@ pub struct MultiTestParser<TIn: Parsable, TFn: Fn(&TIn::T) -> bool> {
     __phantom: std::marker::PhantomData<TIn>,
 }
 
-impl<TIn: Parsable, TFn: Fn(&TIn::T) -> bool, const ERROR: bool> RawTestParser<TIn, ERROR>
+impl<'a, TIn: Parsable, TFn: Fn(&TIn::T) -> bool, const ERROR: bool> RawTestParser<'a, TIn, ERROR>
     for MultiTestParser<TIn, TFn>
 {
     type TOut = TIn::List;
 
-    fn parse(&self, input: &[TIn::T], position: &mut ParsingPosition) -> ParseResult<TIn::List> {
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<TIn::List> {
         let error = if ERROR {
             Some(vec![self.error_str.clone()])
         } else {
@@ -124,6 +193,10 @@ impl<TIn: Parsable, TFn: Fn(&TIn::T) -> bool, const ERROR: bool> RawTestParser<T
             index += 1;
         }
 
+        if let Some(incomplete) = context.incomplete_at(input, index, 1) {
+            return incomplete;
+        }
+
         let slice = position.slice_to(input, index);
 
         if slice.is_empty() {
@@ -145,6 +218,63 @@ pub fn multi_test<TIn: Parsable, TFn: Fn(&TIn::T) -> bool + Clone>(
     }
 }
 
+#[derive(Clone)]
+pub struct MultiTestSliceParser<TIn: Parsable, TFn: Fn(&TIn::T) -> bool> {
+    test_fn: TFn,
+    error_str: String,
+    __phantom: std::marker::PhantomData<TIn>,
+}
+
+impl<'a, TIn: Parsable, TFn: Fn(&TIn::T) -> bool, const ERROR: bool> RawTestParser<'a, TIn, ERROR>
+    for MultiTestSliceParser<TIn, TFn>
+where
+    TIn::T: 'a,
+{
+    type TOut = &'a [TIn::T];
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<&'a [TIn::T]> {
+        let error = if ERROR {
+            Some(vec![self.error_str.clone()])
+        } else {
+            None
+        };
+
+        let mut index = position.index();
+        while index < input.len() && (self.test_fn)(&input[index]) {
+            index += 1;
+        }
+
+        if let Some(incomplete) = context.incomplete_at(input, index, 1) {
+            return incomplete;
+        }
+
+        let slice = position.slice_to(input, index);
+
+        if slice.is_empty() {
+            position.fail_offset(0, error)
+        } else {
+            position.succeed_at(index.into(), slice)
+        }
+    }
+}
+
+/// Like [`multi_test`], but returns the matched part of the input instead of copying it.
+pub fn multi_test_slice<TIn: Parsable, TFn: Fn(&TIn::T) -> bool + Clone>(
+    test_fn: TFn,
+    error_str: String,
+) -> MultiTestSliceParser<TIn, TFn> {
+    MultiTestSliceParser {
+        test_fn,
+        error_str,
+        __phantom: std::marker::PhantomData,
+    }
+}
+
 #[derive(Clone)]
 pub struct MultiTestWithReduceParser<
     TIn: Parsable,
@@ -169,14 +299,15 @@ impl<
     TFn: Fn(&TIn::T) -> Option<TOut>,
     TRed: Fn(&mut TAcc, TOut, usize),
     const ERROR: bool,
-> RawTestParser<TIn, ERROR> for MultiTestWithReduceParser<TIn, TOut, TAcc, TFn, TRed>
+> RawTestParser<'a, TIn, ERROR> for MultiTestWithReduceParser<TIn, TOut, TAcc, TFn, TRed>
 {
     type TOut = (TAcc, usize);
 
     fn parse(
         &self,
-        input: &[TIn::T],
+        input: &'a [TIn::T],
         position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
     ) -> ParseResult<(TAcc, usize)> {
         let mut index = position.index();
         let mut acc = self.initial.clone();
@@ -191,6 +322,10 @@ impl<
             }
         }
 
+        if let Some(incomplete) = context.incomplete_at(input, index, 1) {
+            return incomplete;
+        }
+
         if index == position.index() {
             let error = if ERROR {
                 Some(vec![self.error_str.clone()])
@@ -235,94 +370,241 @@ pub fn digits<'a>() -> MultiTestParser<char, impl Fn(&char) -> bool> {
 }
 
 #[derive(Clone)]
-pub struct UIntParser;
+pub struct CharTestParser<TIn: TextParsable, TFn: Fn(char) -> bool> {
+    test_fn: TFn,
+    error_str: String,
+    __phantom: std::marker::PhantomData<TIn>,
+}
 
-impl<const ERROR: bool> RawTestParser<char, ERROR> for UIntParser {
-    type TOut = u64;
+impl<'a, TIn: TextParsable, TFn: Fn(char) -> bool, const ERROR: bool> RawTestParser<'a, TIn, ERROR>
+    for CharTestParser<TIn, TFn>
+{
+    type TOut = char;
 
-    fn parse(&self, input: &[char], position: &mut ParsingPosition) -> ParseResult<u64> {
-        let mut index = position.index();
-        let mut num = 0_u64;
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<char> {
+        match TIn::next_char(input, position.index()) {
+            Some((c, width)) if (self.test_fn)(c) => position.succeed_offset(width, c),
+            _ => {
+                if let Some(incomplete) = context.incomplete_text::<TIn, _>(input, position.index())
+                {
+                    return incomplete;
+                }
+
+                let error = if ERROR {
+                    Some(vec![self.error_str.clone()])
+                } else {
+                    None
+                };
+                position.fail_offset(0, error)
+            }
+        }
+    }
+}
 
-        while index < input.len() {
-            if let Some(x) = input[index].to_digit(10) {
-                num = 10_u64 * num + x as u64;
+pub fn char_test<TIn: TextParsable, TFn: Fn(char) -> bool + Clone>(
+    test_fn: TFn,
+    error_str: String,
+) -> CharTestParser<TIn, TFn> {
+    CharTestParser {
+        test_fn,
+        error_str,
+        __phantom: std::marker::PhantomData,
+    }
+}
 
-                index += 1;
-            } else {
+#[derive(Clone)]
+pub struct MultiCharTestParser<TIn: TextParsable, TFn: Fn(char) -> bool> {
+    test_fn: TFn,
+    error_str: String,
+    __phantom: std::marker::PhantomData<TIn>,
+}
+
+impl<'a, TIn: TextParsable, TFn: Fn(char) -> bool, const ERROR: bool> RawTestParser<'a, TIn, ERROR>
+    for MultiCharTestParser<TIn, TFn>
+{
+    type TOut = TIn::List;
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<TIn::List> {
+        let mut index = position.index();
+        while let Some((c, width)) = TIn::next_char(input, index) {
+            if !(self.test_fn)(c) {
                 break;
             }
+            index += width;
         }
 
-        if index == position.index() {
+        if let Some(incomplete) = context.incomplete_text::<TIn, _>(input, index) {
+            return incomplete;
+        }
+
+        let slice = position.slice_to(input, index);
+
+        if slice.is_empty() {
             let error = if ERROR {
-                Some(vec!["a digit".to_string()])
+                Some(vec![self.error_str.clone()])
             } else {
                 None
             };
-            return position.fail_offset(0, error);
+            position.fail_offset(0, error)
+        } else {
+            position.succeed_at(index.into(), TIn::slice_to_list(slice))
         }
-        position.succeed_at(index.into(), num)
     }
 }
 
-pub fn uint() -> UIntParser {
-    UIntParser
+pub fn multi_char_test<TIn: TextParsable, TFn: Fn(char) -> bool + Clone>(
+    test_fn: TFn,
+    error_str: String,
+) -> MultiCharTestParser<TIn, TFn> {
+    MultiCharTestParser {
+        test_fn,
+        error_str,
+        __phantom: std::marker::PhantomData,
+    }
 }
 
+const UINT_FORMAT: IntFormat = IntFormat {
+    minus_sign: false,
+    plus_sign: false,
+    radix: 10,
+    radix_prefixes: false,
+    underscores: false,
+};
+
 #[derive(Clone)]
-pub struct UFloatParser;
+pub struct UIntParser<TIn: TextParsable = char> {
+    __phantom: std::marker::PhantomData<TIn>,
+}
 
-impl<const ERROR: bool> RawTestParser<char, ERROR> for UFloatParser {
-    type TOut = f64;
+impl<TIn: TextParsable> UIntParser<TIn> {
+    pub fn new() -> Self {
+        UIntParser {
+            __phantom: std::marker::PhantomData,
+        }
+    }
+}
 
-    fn parse(&self, input: &[char], position: &mut ParsingPosition) -> ParseResult<f64> {
-        let mut index = position.index();
-        let mut num = 0_f64;
+impl<TIn: TextParsable> Default for UIntParser<TIn> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-        while index < input.len() {
-            if let Some(x) = input[index].to_digit(10) {
-                num = 10_f64 * num + x as f64;
+impl<'a, TIn: TextParsable, const ERROR: bool> RawTestParser<'a, TIn, ERROR> for UIntParser<TIn> {
+    type TOut = u64;
 
-                index += 1;
-            } else {
-                break;
-            }
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<u64> {
+        if let Some(incomplete) =
+            number_parsers::incomplete_number::<TIn, _>(context, input, position.index())
+        {
+            return incomplete;
         }
 
-        if index == position.index() {
-            let error = if ERROR {
-                Some(vec!["a digit".to_string()])
-            } else {
-                None
-            };
-            return position.fail_offset(0, error);
+        match number_parsers::scan_int::<TIn, u64>(&UINT_FORMAT, input, position.index()) {
+            IntScan::Success(num, end) => position.succeed_at(end.into(), num),
+            IntScan::Overflow(end, _) if ERROR => {
+                let span = ParsingRange::new(position.clone(), end.into());
+                ParseResult::Failure(ParseFailure::with_error(
+                    span,
+                    "number too large".to_string(),
+                ))
+            }
+            IntScan::Overflow(_, _) => position.fail_offset(0, None),
+            IntScan::NoDigits => {
+                let error = if ERROR {
+                    Some(vec!["a digit".to_string()])
+                } else {
+                    None
+                };
+                position.fail_offset(0, error)
+            }
         }
+    }
+}
 
-        let comma_index = index;
+pub fn uint() -> UIntParser {
+    UIntParser::new()
+}
 
-        if index >= input.len() || input[index] != '.' {
-            return position.succeed_at(index.into(), num);
+const UFLOAT_FORMAT: FloatFormat = FloatFormat {
+    minus_sign: false,
+    plus_sign: false,
+    leading_dot: false,
+    trailing_dot: true,
+    leading_zeros: true,
+    exponent: false,
+    underscores: false,
+    special_values: false,
+};
+
+#[derive(Clone)]
+pub struct UFloatParser<TIn: TextParsable = char> {
+    __phantom: std::marker::PhantomData<TIn>,
+}
+
+impl<TIn: TextParsable> UFloatParser<TIn> {
+    pub fn new() -> Self {
+        UFloatParser {
+            __phantom: std::marker::PhantomData,
         }
-        index += 1;
+    }
+}
 
-        while index < input.len() {
-            if let Some(x) = input[index].to_digit(10) {
-                let pos = index - comma_index;
-                num = num + (x as f64) * 10_f64.powi(-(pos as i32));
+impl<TIn: TextParsable> Default for UFloatParser<TIn> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-                index += 1;
-            } else {
-                break;
-            }
+impl<'a, TIn: TextParsable, const ERROR: bool> RawTestParser<'a, TIn, ERROR> for UFloatParser<TIn> {
+    type TOut = f64;
+
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<f64> {
+        if let Some(incomplete) =
+            number_parsers::incomplete_number::<TIn, _>(context, input, position.index())
+        {
+            return incomplete;
         }
 
-        position.succeed_at(index.into(), num)
+        let parsed = number_parsers::scan_float::<TIn>(&UFLOAT_FORMAT, input, position.index())
+            .and_then(|scan| Some((scan.value::<TIn, f64>(input)?, scan.end)));
+
+        match parsed {
+            Some((num, end)) => position.succeed_at(end.into(), num),
+            None => {
+                let error = if ERROR {
+                    Some(vec!["a digit".to_string()])
+                } else {
+                    None
+                };
+                position.fail_offset(0, error)
+            }
+        }
     }
 }
 
 pub fn float() -> UFloatParser {
-    UFloatParser
+    UFloatParser::new()
 }
 
 pub fn letter() -> TestParser<char, impl Fn(&char) -> bool> {
@@ -334,15 +616,45 @@ pub fn letters() -> MultiTestParser<char, impl Fn(&char) -> bool> {
 }
 
 #[derive(Clone)]
-pub struct WhiteSpaceParser<const OPTIONAL: bool>;
+pub struct WhiteSpaceParser<const OPTIONAL: bool, TIn: TextParsable = char> {
+    __phantom: std::marker::PhantomData<TIn>,
+}
 
-impl<const ERROR: bool> RawTestParser<char, ERROR> for WhiteSpaceParser<false> {
+impl<const OPTIONAL: bool, TIn: TextParsable> WhiteSpaceParser<OPTIONAL, TIn> {
+    pub fn new() -> Self {
+        WhiteSpaceParser {
+            __phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<const OPTIONAL: bool, TIn: TextParsable> Default for WhiteSpaceParser<OPTIONAL, TIn> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, TIn: TextParsable, const ERROR: bool> RawTestParser<'a, TIn, ERROR>
+    for WhiteSpaceParser<false, TIn>
+{
     type TOut = ();
 
-    fn parse(&self, input: &[char], position: &mut ParsingPosition) -> ParseResult<()> {
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<()> {
         let mut index = position.index();
-        while index < input.len() && input[index].is_whitespace() {
-            index += 1;
+        while let Some((c, width)) = TIn::next_char(input, index) {
+            if !c.is_whitespace() {
+                break;
+            }
+            index += width;
+        }
+
+        if let Some(incomplete) = context.incomplete_text::<TIn, _>(input, index) {
+            return incomplete;
         }
 
         if position.index() == index {
@@ -358,13 +670,27 @@ impl<const ERROR: bool> RawTestParser<char, ERROR> for WhiteSpaceParser<false> {
     }
 }
 
-impl<const ERROR: bool> RawTestParser<char, ERROR> for WhiteSpaceParser<true> {
+impl<'a, TIn: TextParsable, const ERROR: bool> RawTestParser<'a, TIn, ERROR>
+    for WhiteSpaceParser<true, TIn>
+{
     type TOut = ();
 
-    fn parse(&self, input: &[char], position: &mut ParsingPosition) -> ParseResult<()> {
+    fn parse(
+        &self,
+        input: &'a [TIn::T],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<()> {
         let mut index = position.index();
-        while index < input.len() && input[index].is_whitespace() {
-            index += 1;
+        while let Some((c, width)) = TIn::next_char(input, index) {
+            if !c.is_whitespace() {
+                break;
+            }
+            index += width;
+        }
+
+        if let Some(incomplete) = context.incomplete_text::<TIn, _>(input, index) {
+            return incomplete;
         }
 
         position.succeed_at(index.into(), ())
@@ -372,9 +698,9 @@ impl<const ERROR: bool> RawTestParser<char, ERROR> for WhiteSpaceParser<true> {
 }
 
 pub fn whitespace() -> WhiteSpaceParser<false> {
-    WhiteSpaceParser::<false>
+    WhiteSpaceParser::new()
 }
 
 pub fn optional_whitespace() -> WhiteSpaceParser<true> {
-    WhiteSpaceParser::<true>
+    WhiteSpaceParser::new()
 }
@@ -0,0 +1,119 @@
+use rustynom::{
+    binary_parsers::{self, BinaryNumberParser},
+    combinator_parsers::AndParser4,
+    parser::{ParserCombinator, ParserWrapper},
+    transformation_parsers::ManyNonEmptyParser,
+};
+
+#[test]
+fn numbers() {
+    let parser = ParserWrapper::<u8, _, true>::from_parser(
+        AndParser4::new(
+            binary_parsers::be_u16(),
+            binary_parsers::le_u16(),
+            binary_parsers::be_i32(),
+            binary_parsers::le_f32(),
+        )
+        .then_eof(),
+    );
+
+    let mut input = vec![0x01, 0x02, 0x01, 0x02, 0xff, 0xff, 0xff, 0xfe];
+    input.extend(1.5_f32.to_le_bytes());
+    let result = parser.parse_slice(&input);
+    assert_eq!(result.unwrap_success(), (0x0102, 0x0201, -2, 1.5));
+
+    let result = parser.parse_slice(&input[..6]);
+    let failure = result.unwrap_failure();
+    assert_eq!(failure.furthest.index(), 4);
+    assert_eq!(failure.expected, Some(vec!["a big-endian i32".to_string()]));
+}
+
+#[test]
+fn length_prefixed() {
+    let parser = ParserWrapper::<u8, _, true>::from_parser(ManyNonEmptyParser::new(
+        binary_parsers::length_prefixed(
+            BinaryNumberParser::<u8, u8, true>::new(),
+            ManyNonEmptyParser::new(binary_parsers::be_u16()),
+        ),
+    ));
+
+    let result = parser.parse_slice(&[4, 0, 1, 0, 2, 2, 0, 3]);
+    assert_eq!(result.unwrap_success(), vec![vec![1, 2], vec![3]]);
+
+    // the block claims more bytes than there are
+    let result = parser.parse_slice(&[4, 0, 1]);
+    let failure = result.unwrap_failure();
+    assert_eq!(failure.furthest.index(), 3);
+    assert_eq!(failure.expected, Some(vec!["more input".to_string()]));
+}
+
+fn rest_to_vec((_, rest): (&[u8], &[u8])) -> Vec<u8> {
+    rest.to_vec()
+}
+
+#[test]
+fn tag_and_take() {
+    let parser = ParserWrapper::<u8, _, true>::from_parser(
+        binary_parsers::tag(b"\x89PNG")
+            .and(binary_parsers::take(2))
+            .map(rest_to_vec),
+    );
+
+    let result = parser.parse_slice(b"\x89PNG\r\n");
+    assert_eq!(result.unwrap_success(), b"\r\n".to_vec());
+
+    let result = parser.parse_slice(b"GIF89a");
+    let failure = result.unwrap_failure();
+    assert_eq!(
+        failure.expected,
+        Some(vec!["0x89 0x50 0x4e 0x47".to_string()])
+    );
+
+    let result = parser.parse_slice(b"\x89PNG\r");
+    let failure = result.unwrap_failure();
+    assert_eq!(failure.furthest.index(), 4);
+    assert_eq!(failure.expected, Some(vec!["2 items".to_string()]));
+}
+
+#[test]
+fn varint() {
+    let parser = ParserWrapper::<u8, _, true>::from_parser(ManyNonEmptyParser::new(
+        binary_parsers::varint(),
+    ));
+
+    let result = parser.parse_slice(&[0x01, 0x96, 0x01, 0xff, 0xff, 0x03]);
+    assert_eq!(result.unwrap_success(), vec![1, 150, 65535]);
+
+    let result = parser.parse_slice(&[0x80, 0x80]);
+    assert_eq!(
+        result.unwrap_failure().expected,
+        Some(vec!["a varint".to_string()])
+    );
+
+    let mut max = vec![0xff; 9];
+    max.push(0x01);
+    assert_eq!(parser.parse_slice(&max).unwrap_success(), vec![u64::MAX]);
+
+    let mut too_large = vec![0xff; 9];
+    too_large.push(0x7f);
+    let failure = parser.parse_slice(&too_large).unwrap_failure();
+    assert_eq!(failure.error(), Some("varint too large"));
+    assert_eq!(failure.furthest.index(), 0);
+}
+
+#[test]
+fn bit_fields() {
+    // version and header length of an IPv4 header, followed by DSCP and ECN
+    let parser = ParserWrapper::<u8, _, true>::from_parser(
+        binary_parsers::bit_fields([4, 4, 6, 2]).then_eof(),
+    );
+
+    let result = parser.parse_slice(&[0x45, 0b1011_1001]);
+    assert_eq!(result.unwrap_success(), [4, 5, 0b101110, 0b01]);
+
+    let result = parser.parse_slice(&[0x45]);
+    assert_eq!(
+        result.unwrap_failure().expected,
+        Some(vec!["2 bytes of bit fields".to_string()])
+    );
+}
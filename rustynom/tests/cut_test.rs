@@ -0,0 +1,97 @@
+use rustynom::{
+    atomic_parsers::{LiteralParser, LiteralParserNoOutput},
+    combinator_parsers::{AndParser2, SameOrParser2},
+    parse_str,
+    parser::{ParserCombinator, ParserWrapper},
+    transformation_parsers::{ManyParser, OptionalParser},
+};
+
+#[test]
+fn simple_cut() {
+    // '(' commits to the parenthesized alternative
+    let parenthesized = AndParser2::new(
+        LiteralParserNoOutput::new('('),
+        LiteralParser::new('a')
+            .skip(LiteralParserNoOutput::new(')'))
+            .cut(),
+    )
+    .map(|(_, a)| a);
+
+    let parser = ParserWrapper::<char, _, true>::from_parser(SameOrParser2::new(
+        parenthesized,
+        LiteralParser::new('b'),
+    ));
+
+    let result = parse_str!(parser, "(a)");
+    assert_eq!(result.unwrap_success(), 'a');
+
+    let result = parse_str!(parser, "b");
+    assert_eq!(result.unwrap_success(), 'b');
+
+    let result = parse_str!(parser, "(b)");
+    let failure = result.unwrap_failure();
+    assert!(failure.committed);
+    assert_eq!(failure.furthest.index(), 1);
+    assert_eq!(failure.expected, Some(vec!["a".to_string()]));
+}
+
+#[test]
+fn cut_in_many() {
+    let parser = ParserWrapper::<char, Vec<char>>::from_parser(ManyParser::new(
+        LiteralParserNoOutput::new('a')
+            .and(LiteralParser::new('b').cut())
+            .map(|(_, b)| b),
+    ));
+
+    let result = parse_str!(parser, "abab");
+    assert_eq!(result.unwrap_success(), vec!['b', 'b']);
+
+    let result = parse_str!(parser, "abc");
+    assert_eq!(result.unwrap_success(), vec!['b']);
+
+    let result = parse_str!(parser, "abac");
+    let failure = result.unwrap_failure();
+    assert!(failure.committed);
+    assert_eq!(failure.furthest.index(), 3);
+}
+
+#[test]
+fn cut_in_optional() {
+    let parser = ParserWrapper::<char, Option<char>>::from_parser(OptionalParser::new(
+        LiteralParserNoOutput::new('a')
+            .and(LiteralParser::new('b').cut())
+            .map(|(_, b)| b),
+    ));
+
+    let result = parse_str!(parser, "ab");
+    assert_eq!(result.unwrap_success(), Some('b'));
+
+    let result = parse_str!(parser, "c");
+    assert_eq!(result.unwrap_success(), None);
+
+    let result = parse_str!(parser, "ac");
+    assert!(result.is_committed());
+}
+
+#[test]
+fn cut_in_separated_by() {
+    let parser = ParserWrapper::<char, Vec<char>, true>::from_parser(
+        LiteralParser::new('a')
+            .cut()
+            .separated_by(LiteralParserNoOutput::new(','))
+            .surround(
+                LiteralParserNoOutput::new('['),
+                LiteralParserNoOutput::new(']'),
+            ),
+    );
+
+    let result = parse_str!(parser, "[a,a]");
+    assert_eq!(result.unwrap_success(), vec!['a', 'a']);
+
+    // without the cut, the list would end before the dangling ',' and ']' would be expected
+    let result = parse_str!(parser, "[a,b]");
+    let failure = result.unwrap_failure();
+    assert!(failure.committed);
+    assert_eq!(failure.furthest.index(), 3);
+    assert_eq!(failure.expected, Some(vec!["a".to_string()]));
+}
@@ -0,0 +1,101 @@
+use rustynom::{
+    atomic_parsers::LiteralParserNoOutput,
+    parse_str,
+    parser::{DualParserWrapper, Grammar, ParserCombinator, ParserWrapper, Shared},
+    utility_parsers,
+};
+
+// list = '[' (number (',' number)*)? ']' '!'*
+struct List;
+
+impl Grammar<char> for List {
+    type TOut = (Vec<u64>, usize);
+
+    fn define<const ERROR: bool>(&self) -> ParserWrapper<char, Self::TOut, ERROR> {
+        let number = utility_parsers::uint().trim(utility_parsers::optional_whitespace());
+        let tail = LiteralParserNoOutput::new(',')
+            .and(number.clone())
+            .map(|(_, n)| n);
+
+        let list = number
+            .and(tail.many())
+            .map(|(first, mut rest)| {
+                rest.insert(0, first);
+                rest
+            })
+            .optional()
+            .map(Option::unwrap_or_default)
+            .surround(
+                LiteralParserNoOutput::new('['),
+                LiteralParserNoOutput::new(']'),
+            )
+            .label("a list");
+
+        let bangs = LiteralParserNoOutput::new('!')
+            .many()
+            .map(|bangs| bangs.len());
+
+        ParserWrapper::from_parser(list.and(bangs).then_eof())
+    }
+}
+
+#[test]
+fn dual_success() {
+    let parser = DualParserWrapper::from_grammar(&List);
+
+    assert_eq!(parse_str!(parser, "[]").unwrap_success(), (vec![], 0));
+    assert_eq!(
+        parse_str!(parser, "[1, 2,3]!!").unwrap_success(),
+        (vec![1, 2, 3], 2)
+    );
+}
+
+#[test]
+fn dual_failure_is_detailed() {
+    let fast = List.define::<false>();
+    let failure = parse_str!(fast, "[1,]").unwrap_failure();
+    assert_eq!(failure.expected, None);
+
+    let parser = DualParserWrapper::from_grammar(&List);
+
+    let failure = parse_str!(parser, "[1,]").unwrap_failure();
+    assert_eq!(failure.furthest.index(), 2);
+    assert_eq!(failure.expected, Some(vec!["]".to_string()]));
+
+    let failure = parse_str!(parser, "x").unwrap_failure();
+    assert_eq!(failure.message(), "expected a list");
+
+    let failure = parse_str!(parser, "[1]!?").unwrap_failure();
+    assert_eq!(failure.furthest.index(), 4);
+    assert_eq!(failure.expected, Some(vec!["EOF".to_string()]));
+}
+
+// pair = number ':' number
+struct Pair;
+
+impl Grammar<char, Shared> for Pair {
+    type TOut = (u64, u64);
+
+    fn define<const ERROR: bool>(&self) -> ParserWrapper<char, Self::TOut, ERROR, Shared> {
+        let pair = utility_parsers::uint()
+            .skip(LiteralParserNoOutput::new(':'))
+            .and(utility_parsers::uint());
+
+        ParserWrapper::from_parser_sync(pair.then_eof())
+    }
+}
+
+#[test]
+fn dual_shared_grammar() {
+    let parser = DualParserWrapper::from_grammar(&Pair);
+
+    let (pair, failure) = std::thread::scope(|scope| {
+        let pair = scope.spawn(|| parse_str!(parser, "1:2").unwrap_success());
+        let failure = scope.spawn(|| parse_str!(parser, "1;2").unwrap_failure());
+        (pair.join().unwrap(), failure.join().unwrap())
+    });
+
+    assert_eq!(pair, (1, 2));
+    assert_eq!(failure.furthest.index(), 1);
+    assert_eq!(failure.expected, Some(vec![":".to_string()]));
+}
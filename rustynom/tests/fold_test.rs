@@ -0,0 +1,117 @@
+use rustynom::{
+    atomic_parsers::{LiteralListParserNoOutput, LiteralParser, LiteralParserNoOutput},
+    combinator_parsers::SameOrParser2,
+    parse_str,
+    parser::{ParserCombinator, ParserWrapper},
+    utility_parsers,
+};
+
+fn sub(a: i64, b: i64) -> i64 {
+    a - b
+}
+
+fn add(a: i64, b: i64) -> i64 {
+    a + b
+}
+
+fn pow(a: i64, b: i64) -> i64 {
+    a.pow(b as u32)
+}
+
+#[test]
+fn fold_many() {
+    let digit = utility_parsers::test(|c: &char| c.is_ascii_digit(), "a digit".to_string())
+        .map(|c: char| c.to_digit(10).unwrap() as u64);
+    let parser = ParserWrapper::<char, u64, true>::from_parser(
+        digit.fold_many(0, |acc, d| acc * 10 + d).then_eof(),
+    );
+
+    assert_eq!(parse_str!(parser, "1234").unwrap_success(), 1234);
+    assert_eq!(parse_str!(parser, "").unwrap_success(), 0);
+
+    let failure = parse_str!(parser, "12x").unwrap_failure();
+    assert_eq!(failure.furthest.index(), 2);
+}
+
+#[test]
+fn chain_left() {
+    let number = utility_parsers::uint().map(|n| n as i64);
+    let operator = SameOrParser2::new(
+        LiteralParserNoOutput::new('-').map(|_| sub as fn(i64, i64) -> i64),
+        LiteralParserNoOutput::new('+').map(|_| add as fn(i64, i64) -> i64),
+    );
+    let parser = ParserWrapper::<char, i64, true>::from_parser(number.chain_left(operator));
+
+    assert_eq!(parse_str!(parser, "7").unwrap_success(), 7);
+    assert_eq!(parse_str!(parser, "10-2-3").unwrap_success(), 5);
+    assert_eq!(parse_str!(parser, "10-2+3").unwrap_success(), 11);
+}
+
+#[test]
+fn chain_left_dangling_operator() {
+    let number = utility_parsers::uint().map(|n| n as i64);
+    let operator = LiteralParserNoOutput::new('-').map(|_| sub);
+    let parser = ParserWrapper::<char, (i64, char), true>::from_parser(
+        number.chain_left(operator).and(LiteralParser::new('-')),
+    );
+
+    // the trailing operator is not consumed by the chain
+    assert_eq!(parse_str!(parser, "5-1-").unwrap_success(), (4, '-'));
+}
+
+#[test]
+fn chain_right() {
+    let number = utility_parsers::uint().map(|n| n as i64);
+    let operator = LiteralParserNoOutput::new('^').map(|_| pow);
+    let parser =
+        ParserWrapper::<char, i64, true>::from_parser(number.chain_right(operator).then_eof());
+
+    assert_eq!(parse_str!(parser, "2").unwrap_success(), 2);
+    assert_eq!(parse_str!(parser, "2^3^2").unwrap_success(), 512);
+
+    let failure = parse_str!(parser, "2^").unwrap_failure();
+    assert_eq!(failure.furthest.index(), 1);
+}
+
+#[test]
+fn chain_operator_backtracks() {
+    // the operator consumes whitespace before it fails on 'x'
+    let number = || utility_parsers::uint().map(|n| n as i64);
+    let operator = || {
+        utility_parsers::optional_whitespace()
+            .and(LiteralListParserNoOutput::new("**".to_string()))
+            .map(|_| pow)
+    };
+
+    let parser = ParserWrapper::<char, (i64, String), true>::from_parser(
+        number()
+            .chain_right(operator())
+            .and(utility_parsers::remaining::<char>()),
+    );
+    assert_eq!(
+        parse_str!(parser, "2**3 x").unwrap_success(),
+        (8, " x".to_string())
+    );
+
+    let parser = ParserWrapper::<char, (i64, String), true>::from_parser(
+        number()
+            .chain_left(operator())
+            .and(utility_parsers::remaining::<char>()),
+    );
+    assert_eq!(
+        parse_str!(parser, "2**3 x").unwrap_success(),
+        (8, " x".to_string())
+    );
+}
+
+#[test]
+fn long_chain_right() {
+    let number = utility_parsers::uint().map(|n| n as i64);
+    let operator = LiteralParserNoOutput::new('-').map(|_| sub);
+    let parser =
+        ParserWrapper::<char, i64, true>::from_parser(number.chain_right(operator).then_eof());
+
+    // 1-(1-(1-...)) alternates between 0 and 1, and must not recurse per operator
+    let input = vec!["1"; 100_001].join("-").chars().collect::<Vec<_>>();
+    assert_eq!(parser.parse_slice(&input).unwrap_success(), 1);
+}
@@ -5,7 +5,8 @@ use std::fs;
 use rustynom::{
     atomic_parsers::{LiteralListMapParser, LiteralParserNoOutput},
     combinator_parsers::{AndParser5, SameOrParser2, SameOrParser6},
-    parser::{ParserCombinator, ParserWrapper},
+    number_parsers,
+    parser::{ParserCombinator, ParserWrapper, RawTestParser},
     transformation_parsers::RecParser,
     utility_parsers,
 };
@@ -24,7 +25,14 @@ enum Value {
 }
 
 fn define_parser() -> ParserWrapper<char, Value, false> {
-    let number = utility_parsers::float().map(Value::Number);
+    define_parser_with(utility_parsers::float())
+}
+
+fn define_parser_with<P>(number: P) -> ParserWrapper<char, Value, false>
+where
+    P: for<'a> RawTestParser<'a, char, false, TOut = f64> + Clone + 'static,
+{
+    let number = number.map(Value::Number);
 
     let string = utility_parsers::multi_test(|c| *c != '"', "string".to_string())
         .trim(LiteralParserNoOutput::new('"'));
@@ -42,11 +50,12 @@ fn define_parser() -> ParserWrapper<char, Value, false> {
         let array = rec_ref
             .clone()
             .separated_by(LiteralParserNoOutput::new(','))
+            .allow_empty()
             .surround(
                 LiteralParserNoOutput::new('['),
                 LiteralParserNoOutput::new(']'),
             )
-            // .box_describe("array")
+            .label("array")
             .map(Value::Array);
 
         let object_entry = AndParser5::new(
@@ -56,11 +65,12 @@ fn define_parser() -> ParserWrapper<char, Value, false> {
             LiteralParserNoOutput::new(':'),
             rec_ref.clone(),
         )
-        // .box_describe("object property")
+        .label("object property")
         .map(|(_, key, _, _, value)| (key, value));
 
         let object = object_entry
             .separated_by(LiteralParserNoOutput::new(','))
+            .allow_empty()
             .surround(
                 LiteralParserNoOutput::new('{'),
                 LiteralParserNoOutput::new('}'),
@@ -87,6 +97,25 @@ fn define_parser() -> ParserWrapper<char, Value, false> {
 
 // }
 
+#[test]
+fn json_numbers() {
+    let parser = define_parser_with(number_parsers::json_number());
+    let chars = r#"[-1.5e3, 0, {"a": 2E-2}]"#.chars().collect::<Vec<_>>();
+
+    assert_eq!(
+        parser.parse_slice(&chars).unwrap_success(),
+        Value::Array(vec![
+            Value::Number(-1500.0),
+            Value::Number(0.0),
+            Value::Object(vec![("a".to_string(), Value::Number(0.02))]),
+        ])
+    );
+
+    // the JSON grammar rejects leading zeros
+    let chars = "[01]".chars().collect::<Vec<_>>();
+    assert!(parser.parse_slice(&chars).is_failure());
+}
+
 #[bench]
 fn bench_json(b: &mut Bencher) {
     let json_string = fs::read_to_string("tests/data/big_json.json").unwrap();
@@ -0,0 +1,73 @@
+use rustynom::{
+    atomic_parsers::{LiteralParser, LiteralParserNoOutput},
+    combinator_parsers::{AndParser2, SameOrParser2},
+    parse_str,
+    parser::{ParserCombinator, ParserWrapper},
+    transformation_parsers::RecParser,
+    utility_parsers,
+};
+
+#[test]
+fn simple_label() {
+    let parser = ParserWrapper::<char, _, true>::from_parser(
+        SameOrParser2::new(LiteralParser::new('a'), LiteralParser::new('b')).label("letter"),
+    );
+
+    let result = parse_str!(parser, "a");
+    assert!(result.is_success());
+
+    let result = parse_str!(parser, "c");
+    assert!(result.is_failure());
+    let failure = result.unwrap_failure();
+    assert_eq!(failure.furthest.index(), 0);
+    assert_eq!(failure.expected, Some(vec!["letter".to_string()]));
+}
+
+#[test]
+fn label_keeps_inner_failure_after_consuming() {
+    let parser = ParserWrapper::<char, _, true>::from_parser(
+        AndParser2::new(LiteralParser::new('a'), LiteralParser::new('b')).label("ab"),
+    );
+
+    let result = parse_str!(parser, "c");
+    let failure = result.unwrap_failure();
+    assert_eq!(failure.expected, Some(vec!["ab".to_string()]));
+
+    let result = parse_str!(parser, "ac");
+    let failure = result.unwrap_failure();
+    assert_eq!(failure.furthest.index(), 1);
+    assert_eq!(failure.expected, Some(vec!["b".to_string()]));
+}
+
+#[test]
+fn simple_context() {
+    let rec = RecParser::new(|rec_ref| {
+        let array = rec_ref
+            .separated_by(LiteralParserNoOutput::new(','))
+            .surround(
+                LiteralParserNoOutput::new('['),
+                LiteralParserNoOutput::new(']'),
+            )
+            .map(|values| values.into_iter().sum::<u64>())
+            .context("in array");
+
+        SameOrParser2::new(utility_parsers::uint().label("value"), array)
+    });
+
+    let parser = ParserWrapper::<char, u64, true>::from_parser(rec);
+
+    let result = parse_str!(parser, "[1,[2,3]]");
+    assert_eq!(result.unwrap_success(), 6);
+
+    let result = parse_str!(parser, "[[x]]");
+    let failure = result.unwrap_failure();
+    assert_eq!(failure.furthest.index(), 2);
+    assert_eq!(
+        failure.context(),
+        vec!["in array".to_string(), "in array".to_string()]
+    );
+    assert_eq!(
+        failure.message(),
+        "expected one of: [, value in array in array"
+    );
+}
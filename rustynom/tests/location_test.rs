@@ -0,0 +1,52 @@
+use rustynom::{
+    ParsingRange,
+    atomic_parsers::LiteralListParser,
+    location::{LineColumn, LineIndex},
+    parse_str,
+    parser::ParserWrapper,
+};
+
+#[test]
+fn simple_line_column() {
+    let chars = "ab\ncd\n\nef".chars().collect::<Vec<_>>();
+    let index = LineIndex::new::<char>(&chars);
+
+    assert_eq!(index.line_count(), 4);
+    assert_eq!(index.line_column(&0.into()), LineColumn::new(1, 1));
+    assert_eq!(index.line_column(&2.into()), LineColumn::new(1, 3));
+    assert_eq!(index.line_column(&3.into()), LineColumn::new(2, 1));
+    assert_eq!(index.line_column(&6.into()), LineColumn::new(3, 1));
+    assert_eq!(index.line_column(&8.into()), LineColumn::new(4, 2));
+    // the end of the input is still a valid position
+    assert_eq!(index.line_column(&9.into()), LineColumn::new(4, 3));
+
+    assert_eq!(index.line_range(2), ParsingRange::new(3.into(), 5.into()));
+    assert_eq!(index.line_range(3), ParsingRange::new(6.into(), 6.into()));
+    assert_eq!(index.line_range(4), ParsingRange::new(7.into(), 9.into()));
+}
+
+#[test]
+fn simple_span() {
+    let chars = "ab\ncd".chars().collect::<Vec<_>>();
+    let index = LineIndex::new::<char>(&chars);
+
+    let span = ParsingRange::new(1.into(), 4.into()).span(&index);
+    assert_eq!(span.start, LineColumn::new(1, 2));
+    assert_eq!(span.end, LineColumn::new(2, 2));
+    assert!(span.is_multiline());
+    assert_eq!(span.to_string(), "1:2-2:2");
+}
+
+#[test]
+fn failure_line_column() {
+    let parser =
+        ParserWrapper::<char, _, true>::from_parser(LiteralListParser::new("a\nb".to_string()));
+
+    let result = parse_str!(parser, "a\nc");
+    assert!(result.is_failure());
+
+    let chars = "a\nc".chars().collect::<Vec<_>>();
+    let index = LineIndex::new::<char>(&chars);
+    let failure = result.unwrap_failure();
+    assert_eq!(failure.line_column(&index), LineColumn::new(1, 1));
+}
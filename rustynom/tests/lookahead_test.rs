@@ -0,0 +1,96 @@
+use rustynom::{
+    ParsingPosition,
+    atomic_parsers::{LiteralListParser, LiteralParser, LiteralParserNoOutput},
+    context::ParseContext,
+    parse_str,
+    parser::{ParserCombinator, ParserWrapper, RawTestParser},
+    utility_parsers,
+};
+
+fn identifier_char() -> impl for<'a> ParserCombinator<'a, char, true, TOut = char> {
+    utility_parsers::test(
+        |c: &char| c.is_alphanumeric() || *c == '_',
+        "identifier character".to_string(),
+    )
+}
+
+#[test]
+fn not() {
+    let keyword = ParserWrapper::<char, String, true>::from_parser(
+        LiteralListParser::new("if".to_string()).skip(identifier_char().not()),
+    );
+
+    assert_eq!(parse_str!(keyword, "if").unwrap_success(), "if");
+    assert_eq!(parse_str!(keyword, "if (").unwrap_success(), "if");
+
+    let failure = parse_str!(keyword, "iffy").unwrap_failure();
+    assert_eq!(failure.furthest.index(), 2);
+    assert_eq!(failure.message(), r#"expected not followed by "f""#);
+
+    let keyword = ParserWrapper::<char, String, true>::from_parser(
+        LiteralListParser::new("if".to_string()).skip(
+            identifier_char()
+                .not()
+                .label("not followed by identifier character"),
+        ),
+    );
+    let failure = parse_str!(keyword, "iffy").unwrap_failure();
+    assert_eq!(
+        failure.message(),
+        "expected not followed by identifier character"
+    );
+}
+
+#[test]
+fn and_predicate() {
+    let call = ParserWrapper::<char, (String, char), true>::from_parser(
+        utility_parsers::multi_test(|c: &char| c.is_alphabetic(), "a letter".to_string())
+            .skip(LiteralParserNoOutput::new('(').and_predicate())
+            .and(LiteralParser::new('(')),
+    );
+
+    assert_eq!(
+        parse_str!(call, "print(").unwrap_success(),
+        ("print".to_string(), '(')
+    );
+
+    let failure = parse_str!(call, "print[").unwrap_failure();
+    assert_eq!(failure.furthest.index(), 5);
+    assert_eq!(failure.expected, Some(vec!["(".to_string()]));
+}
+
+#[test]
+fn peek() {
+    let parser = ParserWrapper::<char, (char, char), true>::from_parser(
+        LiteralParser::new('a').peek().and(LiteralParser::new('a')),
+    );
+
+    assert_eq!(parse_str!(parser, "a").unwrap_success(), ('a', 'a'));
+    assert!(parse_str!(parser, "b").is_failure());
+}
+
+#[test]
+fn peek_rewinds() {
+    let parser = LiteralParser::new('a').and(LiteralParser::new('b')).peek();
+    let input = ['a', 'x'];
+
+    let mut position = ParsingPosition::default();
+    let result = RawTestParser::<char, true>::parse(
+        &parser,
+        &input,
+        &mut position,
+        &mut ParseContext::new(),
+    );
+    assert_eq!(result.unwrap_failure().furthest.index(), 1);
+    assert_eq!(position.index(), 0);
+
+    let input = ['a', 'b'];
+    let result = RawTestParser::<char, true>::parse(
+        &parser,
+        &input,
+        &mut position,
+        &mut ParseContext::new(),
+    );
+    assert!(result.is_success());
+    assert_eq!(position.index(), 0);
+}
@@ -1,9 +1,10 @@
 use rustynom::{
-    atomic_parsers::{LiteralParser, LiteralParserNoOutput},
-    combinator_parsers::AndParser2,
+    atomic_parsers::{LiteralParser, LiteralParserNoOutput, SuccessParser},
+    combinator_parsers::{AndParser2, SameOrParser2, Trailing},
     parse_str,
     parser::{ParserCombinator, ParserWrapper},
-    transformation_parsers::ManyParser,
+    transformation_parsers::{ManyNonEmptyParser, ManyParser},
+    utility_parsers,
 };
 
 #[test]
@@ -152,3 +153,119 @@ fn simple_separated_by_2() {
     let result: rustynom::ParseResult<Vec<char>> = parse_str!(parser, "[]");
     assert!(result.is_failure());
 }
+
+#[test]
+fn separated_by_empty() {
+    let parser = ParserWrapper::<char, Vec<char>, true>::from_parser(
+        LiteralParser::new('a')
+            .separated_by(LiteralParserNoOutput::new(','))
+            .allow_empty()
+            .surround(
+                LiteralParserNoOutput::new('['),
+                LiteralParserNoOutput::new(']'),
+            ),
+    );
+
+    assert_eq!(parse_str!(parser, "[]").unwrap_success(), vec![]);
+    assert_eq!(parse_str!(parser, "[a,a]").unwrap_success(), vec!['a', 'a']);
+}
+
+#[test]
+fn separated_by_trailing() {
+    let list = LiteralParser::new('a').separated_by(LiteralParserNoOutput::new(','));
+
+    // by default a trailing separator is left for the next parser
+    let parser = ParserWrapper::<char, (Vec<char>, char), true>::from_parser(
+        list.clone().and(LiteralParser::new(',')).then_eof(),
+    );
+    assert_eq!(
+        parse_str!(parser, "a,a,").unwrap_success(),
+        (vec!['a', 'a'], ',')
+    );
+
+    let parser = ParserWrapper::<char, Vec<char>, true>::from_parser(
+        list.clone().trailing(Trailing::Forbid),
+    );
+    let failure = parse_str!(parser, "a,a,").unwrap_failure();
+    assert_eq!(failure.furthest.index(), 4);
+    assert_eq!(failure.expected, Some(vec!["a".to_string()]));
+
+    let parser = ParserWrapper::<char, Vec<char>, true>::from_parser(
+        list.clone().trailing(Trailing::Allow).then_eof(),
+    );
+    assert_eq!(parse_str!(parser, "a,a,").unwrap_success(), vec!['a', 'a']);
+    assert_eq!(parse_str!(parser, "a,a").unwrap_success(), vec!['a', 'a']);
+
+    let parser = ParserWrapper::<char, Vec<char>, true>::from_parser(
+        list.trailing(Trailing::Require).allow_empty().then_eof(),
+    );
+    assert_eq!(parse_str!(parser, "a,a,").unwrap_success(), vec!['a', 'a']);
+    assert_eq!(parse_str!(parser, "").unwrap_success(), vec![]);
+    let failure = parse_str!(parser, "a,a").unwrap_failure();
+    assert_eq!(failure.furthest.index(), 3);
+    assert_eq!(failure.expected, Some(vec![",".to_string()]));
+}
+
+#[test]
+fn separated_by_keep() {
+    let parser = ParserWrapper::<char, (Vec<char>, Vec<char>), true>::from_parser(
+        LiteralParser::new('a')
+            .separated_by_keep(SameOrParser2::new(
+                LiteralParser::new(','),
+                LiteralParser::new(';'),
+            ))
+            .then_eof(),
+    );
+
+    assert_eq!(
+        parse_str!(parser, "a,a;a").unwrap_success(),
+        (vec!['a', 'a', 'a'], vec![',', ';'])
+    );
+}
+
+#[test]
+fn many_without_progress() {
+    let parser = ParserWrapper::<char, Vec<()>>::from_parser(ManyParser::new(
+        utility_parsers::optional_whitespace(),
+    ));
+
+    let failure = parse_str!(parser, "  a").unwrap_failure();
+    assert!(failure.committed);
+    assert_eq!(failure.furthest.index(), 2);
+    assert!(
+        failure.message().contains("WhiteSpaceParser"),
+        "{}",
+        failure.message()
+    );
+
+    let parser = ParserWrapper::<char, Vec<char>, true>::from_parser(ManyNonEmptyParser::new(
+        SuccessParser::new('x'),
+    ));
+    assert!(parse_str!(parser, "").unwrap_failure().committed);
+}
+
+#[test]
+fn no_progress_is_not_backtracked() {
+    // the failure points at the broken grammar instead of being hidden by the alternative
+    let parser = ParserWrapper::<char, Vec<char>>::from_parser(SameOrParser2::new(
+        ManyParser::new(SuccessParser::new('a')),
+        ManyParser::new(LiteralParser::new('b')),
+    ));
+
+    assert!(parse_str!(parser, "b").unwrap_failure().committed);
+}
+
+#[test]
+fn separated_by_without_progress() {
+    let parser = ParserWrapper::<char, Vec<char>>::from_parser(
+        SuccessParser::new('a').separated_by(utility_parsers::optional_whitespace()),
+    );
+
+    let failure = parse_str!(parser, "a").unwrap_failure();
+    assert!(failure.committed);
+    assert!(
+        failure
+            .message()
+            .starts_with("repeated parser succeeded without consuming input")
+    );
+}
@@ -0,0 +1,147 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use rustynom::{
+    ParseResult, ParsingPosition,
+    atomic_parsers::LiteralParserNoOutput,
+    combinator_parsers::{AndParser3, SameOrParser2, SameOrParser3},
+    context::ParseContext,
+    parser::{Input, Local, ParserCombinator, ParserWrapper, RawTestParser},
+    recovery_parsers::skip_until,
+    transformation_parsers::{RecParser, RecRefParser},
+    utility_parsers,
+};
+
+#[derive(Clone)]
+struct CountedDigit {
+    calls: Arc<AtomicUsize>,
+}
+
+impl<'a> RawTestParser<'a, char, true> for CountedDigit {
+    type TOut = i64;
+
+    fn parse(
+        &self,
+        input: &'a [char],
+        position: &mut ParsingPosition,
+        _context: &mut ParseContext<'a>,
+    ) -> ParseResult<i64> {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        match position.test_current(input, |c| c.is_ascii_digit()) {
+            true => {
+                position.succeed_offset(1, position.current(input).to_digit(10).unwrap() as i64)
+            }
+            false => position.fail_offset(0, Some(vec!["a digit".to_string()])),
+        }
+    }
+}
+
+// expr = term '+' expr | term '-' expr | term
+// term = '(' expr ')' | digit
+fn expression(calls: Arc<AtomicUsize>, memoize: bool) -> ParserWrapper<char, i64, true> {
+    let rec = RecParser::new(|expr| {
+        let parenthesized = expr.clone().surround(
+            LiteralParserNoOutput::new('('),
+            LiteralParserNoOutput::new(')'),
+        );
+        let term = SameOrParser2::new(parenthesized, CountedDigit { calls });
+        let term_ref = RecRefParser::new();
+        if memoize {
+            term_ref.set(Box::new(term.memoize()));
+        } else {
+            term_ref.set(Box::new(term));
+        }
+        let term = term_ref;
+
+        SameOrParser3::new(
+            AndParser3::new(term.clone(), LiteralParserNoOutput::new('+'), expr.clone())
+                .map(|(a, _, b)| a + b),
+            AndParser3::new(term.clone(), LiteralParserNoOutput::new('-'), expr)
+                .map(|(a, _, b)| a - b),
+            term,
+        )
+    });
+
+    ParserWrapper::from_parser(rec.then_eof())
+}
+
+#[test]
+fn memoize_nested_expression() {
+    let input = "((((((((((1+2))))))))))-3".chars().collect::<Vec<_>>();
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let result = expression(calls.clone(), false).parse_slice(&input);
+    assert_eq!(result.unwrap_success(), 0);
+    let unmemoized_calls = calls.load(Ordering::Relaxed);
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let result = expression(calls.clone(), true).parse_slice(&input);
+    assert_eq!(result.unwrap_success(), 0);
+    let memoized_calls = calls.load(Ordering::Relaxed);
+
+    assert!(unmemoized_calls > 10_000, "{}", unmemoized_calls);
+    // the digit parser runs at most once per position
+    assert!(memoized_calls <= input.len(), "{}", memoized_calls);
+}
+
+#[test]
+fn memoize_replays_diagnostics() {
+    let item = utility_parsers::uint()
+        .skip(LiteralParserNoOutput::new(';'))
+        .recover_with(skip_until(';').map(|_| 0))
+        .memoize();
+
+    // both alternatives start with the same item, the second one hits the memo table
+    let parser = ParserWrapper::<char, u64, true>::from_parser(SameOrParser2::new(
+        item.clone().skip(LiteralParserNoOutput::new('!')),
+        item,
+    ));
+
+    let chars = "x;".chars().collect::<Vec<_>>();
+    let (output, errors) = parser.parse_slice_recovering(&chars);
+    assert_eq!(output, Some(0));
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].expected, Some(vec!["a digit".to_string()]));
+}
+
+#[test]
+fn memoize_left_recursion() {
+    // expr = expr '-' digit | digit, with the left recursive call memoized
+    let digit = utility_parsers::test(|c: &char| c.is_ascii_digit(), "a digit".to_string())
+        .map(|c: char| c.to_digit(10).unwrap() as i64);
+
+    let rec = RecParser::new(|expr| {
+        SameOrParser2::new(
+            AndParser3::new(
+                expr.memoize(),
+                LiteralParserNoOutput::new('-'),
+                digit.clone(),
+            )
+            .map(|(a, _, b)| a - b),
+            digit,
+        )
+    });
+    let parser = ParserWrapper::<char, i64, true>::from_parser(rec.then_eof());
+
+    // the memo entries written for a smaller seed must not stop the seed from growing
+    let input = "9-2-3".chars().collect::<Vec<_>>();
+    assert_eq!(parser.parse_slice(&input).unwrap_success(), 4);
+}
+
+#[test]
+fn memoize_borrowed_output() {
+    let chars = "ab".chars().collect::<Vec<_>>();
+    let word = utility_parsers::multi_test(|c: &char| c.is_ascii_lowercase(), "a word".to_string())
+        .recognize()
+        .memoize();
+
+    // the second alternative reads the slice the first one stored in the memo table
+    let parser = ParserWrapper::<char, &[char], true, Local, Input>::from_parser_borrowed(
+        SameOrParser2::new(word.clone().skip(LiteralParserNoOutput::new('!')), word),
+    );
+
+    let word = parser.parse_slice(&chars).unwrap_success();
+    assert!(std::ptr::eq(word, &chars[..]));
+}
@@ -0,0 +1,239 @@
+use rustynom::{
+    ParseResult, ParsingRange,
+    atomic_parsers::LiteralParserNoOutput,
+    combinator_parsers::SameOrParser2,
+    number_parsers::{self, FloatFormat, FloatParser, IntFormat, IntParser},
+    parsable::Utf8,
+    parse_str,
+    parser::{ParserCombinator, ParserWrapper},
+    utility_parsers,
+};
+
+fn parse<TOut: Clone>(parser: &ParserWrapper<char, TOut, true>, input: &str) -> ParseResult<TOut> {
+    let chars = input.chars().collect::<Vec<_>>();
+    parser.parse_slice(&chars)
+}
+
+#[test]
+fn json_number() {
+    let parser =
+        ParserWrapper::<char, f64, true>::from_parser(number_parsers::json_number().then_eof());
+
+    for (input, expected) in [
+        ("0", 0.0),
+        ("-1", -1.0),
+        ("1.1", 1.1),
+        ("0.30000000000000004", 0.30000000000000004),
+        ("1e10", 1e10),
+        ("-2.5E-3", -2.5e-3),
+    ] {
+        assert_eq!(
+            parse(&parser, input).unwrap_success(),
+            expected,
+            "{}",
+            input
+        );
+    }
+
+    for input in [".5", "1.", "+1", "01", "1_000", "NaN", "-"] {
+        assert!(parse(&parser, input).is_failure(), "{}", input);
+    }
+
+    let failure = parse_str!(parser, "x").unwrap_failure();
+    assert_eq!(failure.expected, Some(vec!["a number".to_string()]));
+}
+
+#[test]
+fn rust_float() {
+    let parser =
+        ParserWrapper::<char, f64, true>::from_parser(number_parsers::rust_float().then_eof());
+
+    for (input, expected) in [
+        ("1_000.5", 1000.5),
+        ("1.", 1.0),
+        ("007", 7.0),
+        ("1e1_0", 1e10),
+    ] {
+        assert_eq!(
+            parse(&parser, input).unwrap_success(),
+            expected,
+            "{}",
+            input
+        );
+    }
+
+    for input in ["-1", "_1", ".5", "1._5", "inf"] {
+        assert!(parse(&parser, input).is_failure(), "{}", input);
+    }
+
+    // a dangling exponent is left for the next parser
+    let parser = ParserWrapper::<char, f64, true>::from_parser(number_parsers::rust_float());
+    let chars = "2e".chars().collect::<Vec<_>>();
+    assert_eq!(parser.parse_slice(&chars).unwrap_success(), 2.0);
+
+    // so is the dot of a range or a method call
+    for input in ["1..2", "1.max(2)", "1._x", "1.e5"] {
+        let chars = input.chars().collect::<Vec<_>>();
+        let (n, position) = parser.parse_prefix(&chars).unwrap_success();
+        assert_eq!((n, position.index()), (1.0, 1), "{}", input);
+    }
+}
+
+#[test]
+fn permissive_float() {
+    let parser = ParserWrapper::<char, f64, true>::from_parser(
+        number_parsers::permissive_float().then_eof(),
+    );
+
+    for (input, expected) in [
+        ("+.5", 0.5),
+        ("-1.", -1.0),
+        ("inf", f64::INFINITY),
+        ("-Infinity", f64::NEG_INFINITY),
+    ] {
+        assert_eq!(
+            parse(&parser, input).unwrap_success(),
+            expected,
+            "{}",
+            input
+        );
+    }
+
+    assert!(parse_str!(parser, "NaN").unwrap_success().is_nan());
+}
+
+#[test]
+fn custom_format() {
+    let parser = ParserWrapper::<Utf8, f32, true>::from_parser(
+        FloatParser::<Utf8, f32>::new(FloatFormat::RUST.signed(true)).then_eof(),
+    );
+
+    let result = parser.parse_slice("-1_0.25".as_bytes());
+    assert_eq!(result.unwrap_success(), -10.25_f32);
+
+    let result = parser.parse_slice("+3".as_bytes());
+    assert_eq!(result.unwrap_success(), 3.0_f32);
+
+    let result = parser.parse_slice("0.1".as_bytes());
+    assert_eq!(result.unwrap_success(), 0.1_f32);
+}
+
+#[test]
+fn decimal_int() {
+    let parser =
+        ParserWrapper::<char, i8, true>::from_parser(number_parsers::int::<i8>().then_eof());
+
+    for (input, expected) in [("0", 0), ("127", 127), ("-128", -128), ("007", 7)] {
+        assert_eq!(
+            parse(&parser, input).unwrap_success(),
+            expected,
+            "{}",
+            input
+        );
+    }
+
+    let failure = parse(&parser, "128").unwrap_failure();
+    assert_eq!(failure.error(), Some("number too large"));
+    assert_eq!(failure.span(), Some(&ParsingRange::new(0.into(), 3.into())));
+
+    let failure = parse(&parser, "-129").unwrap_failure();
+    assert_eq!(failure.error(), Some("number too small"));
+
+    let failure = parse(&parser, "+1").unwrap_failure();
+    assert_eq!(failure.expected, Some(vec!["an integer".to_string()]));
+}
+
+#[test]
+fn overflow_in_alternative() {
+    let flag = || LiteralParserNoOutput::new('x').map(|_| 0_u8);
+
+    // the error and context are kept whichever side of the alternative they come from
+    let parser = ParserWrapper::<char, u8, true>::from_parser(SameOrParser2::new(
+        flag(),
+        number_parsers::int::<u8>().context("in a port"),
+    ));
+    let failure = parse(&parser, "300").unwrap_failure();
+    assert_eq!(failure.message(), "number too large in a port");
+    assert_eq!(failure.span(), Some(&ParsingRange::new(0.into(), 3.into())));
+
+    let parser = ParserWrapper::<char, u8, true>::from_parser(SameOrParser2::new(
+        number_parsers::int::<u8>(),
+        flag(),
+    ));
+    let failure = parse(&parser, "300").unwrap_failure();
+    assert_eq!(failure.message(), "number too large");
+    assert_eq!(failure.expected_items(), vec!["x"]);
+}
+
+#[test]
+fn unsigned_int() {
+    let parser =
+        ParserWrapper::<char, u32, true>::from_parser(number_parsers::int::<u32>().then_eof());
+
+    assert_eq!(parse(&parser, "4294967295").unwrap_success(), u32::MAX);
+    assert!(parse(&parser, "-1").is_failure());
+
+    let failure = parse(&parser, "4294967296").unwrap_failure();
+    assert_eq!(failure.error(), Some("number too large"));
+}
+
+#[test]
+fn rust_int() {
+    let parser =
+        ParserWrapper::<char, u16, true>::from_parser(number_parsers::rust_int::<u16>().then_eof());
+
+    for (input, expected) in [
+        ("1_000", 1000),
+        ("0xff_ff", 0xffff),
+        ("0o17", 0o17),
+        ("0B101", 0b101),
+        ("0", 0),
+    ] {
+        assert_eq!(
+            parse(&parser, input).unwrap_success(),
+            expected,
+            "{}",
+            input
+        );
+    }
+
+    for input in ["_1", "0x", "0xg", "-1"] {
+        assert!(parse(&parser, input).is_failure(), "{}", input);
+    }
+
+    let failure = parse(&parser, "0x1_0000").unwrap_failure();
+    assert_eq!(failure.span(), Some(&ParsingRange::new(0.into(), 8.into())));
+}
+
+#[test]
+fn custom_int_format() {
+    let parser = ParserWrapper::<Utf8, i32, true>::from_parser(
+        IntParser::<Utf8, i32>::new(IntFormat::DECIMAL.signed(true).radix(16)).then_eof(),
+    );
+
+    let result = parser.parse_slice("+7f".as_bytes());
+    assert_eq!(result.unwrap_success(), 0x7f);
+
+    let result = parser.parse_slice("-80000000".as_bytes());
+    assert_eq!(result.unwrap_success(), i32::MIN);
+}
+
+#[test]
+fn uint_overflow() {
+    let parser = ParserWrapper::<char, u64, true>::from_parser(
+        utility_parsers::uint().skip(LiteralParserNoOutput::new(';')),
+    );
+
+    assert_eq!(
+        parse(&parser, "18446744073709551615;").unwrap_success(),
+        u64::MAX
+    );
+
+    let chars = "99999999999999999999;".chars().collect::<Vec<_>>();
+    let failure = parser.parse_slice(&chars).unwrap_failure();
+    assert_eq!(failure.message(), "number too large");
+    assert_eq!(
+        failure.report::<char>(&chars).to_string(),
+        "error: number too large\n --> 1:1\n  |\n1 | 99999999999999999999;\n  | ^^^^^^^^^^^^^^^^^^^^"
+    );
+}
@@ -0,0 +1,80 @@
+use std::io;
+
+use rustynom::{
+    atomic_parsers::LiteralParserNoOutput,
+    combinator_parsers::Trailing,
+    parsable::Utf8,
+    parser::{ParserCombinator, ParserWrapper},
+    utility_parsers::{self, UIntParser},
+};
+
+fn numbers() -> ParserWrapper<char, Vec<u64>, true> {
+    ParserWrapper::from_parser(
+        utility_parsers::uint()
+            .separated_by(LiteralParserNoOutput::new(','))
+            .trailing(Trailing::Forbid)
+            .then_eof(),
+    )
+}
+
+#[test]
+fn parse_str() {
+    let parser = numbers();
+
+    let input = (1..=3).map(|n| n.to_string()).collect::<Vec<_>>().join(",");
+    assert_eq!(
+        parser.parse_str(&input).result.unwrap_success(),
+        vec![1, 2, 3]
+    );
+    assert_eq!(
+        parser.parse_str(input).into_result().unwrap_success(),
+        vec![1, 2, 3]
+    );
+
+    let parsed = parser.parse_str("1,x");
+    assert!(parsed.result.is_failure());
+    assert_eq!(
+        parsed.report().unwrap().to_string(),
+        "error: expected a digit\n --> 1:3\n  |\n1 | 1,x\n  |   ^"
+    );
+    assert!(parser.parse_str("1").report().is_none());
+}
+
+#[test]
+fn parse_bytes() {
+    let parser = ParserWrapper::<Utf8, Vec<u64>, true>::from_parser(
+        UIntParser::new()
+            .separated_by(LiteralParserNoOutput::new(b','))
+            .then_eof(),
+    );
+    assert_eq!(
+        parser.parse_bytes(b"4,5").unwrap().result.unwrap_success(),
+        vec![4, 5]
+    );
+
+    let parser = numbers();
+    assert_eq!(
+        parser.parse_bytes(b"6").unwrap().result.unwrap_success(),
+        vec![6]
+    );
+    assert!(parser.parse_bytes(&[b'1', 0xff]).is_err());
+}
+
+#[test]
+fn parse_reader_and_file() {
+    let parser = numbers();
+
+    let parsed = parser.parse_reader(io::Cursor::new("7,8")).unwrap();
+    assert_eq!(parsed.result.unwrap_success(), vec![7, 8]);
+
+    let error = parser.parse_reader(&[0xc3_u8][..]).err().unwrap();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+    let path = std::env::temp_dir().join(format!("rustynom_{}.txt", std::process::id()));
+    std::fs::write(&path, "9,10").unwrap();
+    let parsed = parser.parse_file(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(parsed.unwrap().result.unwrap_success(), vec![9, 10]);
+
+    assert!(parser.parse_file("does/not/exist").is_err());
+}
@@ -0,0 +1,47 @@
+use rustynom::{
+    ParsingRange,
+    atomic_parsers::LiteralParserNoOutput,
+    parser::{ParserCombinator, ParserWrapper},
+    utility_parsers,
+};
+
+fn statement() -> ParserWrapper<char, u64, true> {
+    ParserWrapper::from_parser(utility_parsers::uint().skip(LiteralParserNoOutput::new(';')))
+}
+
+#[test]
+fn parse_prefix() {
+    let parser = statement();
+    let chars = "1;22;333;".chars().collect::<Vec<_>>();
+
+    // consume the input one statement at a time
+    let mut rest = &chars[..];
+    let mut statements = Vec::new();
+    while !rest.is_empty() {
+        let (n, position) = parser.parse_prefix(rest).unwrap_success();
+        statements.push(n);
+        rest = position.slice(rest);
+    }
+    assert_eq!(statements, vec![1, 22, 333]);
+
+    let failure = parser.parse_prefix(&['x']).unwrap_failure();
+    assert_eq!(failure.expected, Some(vec!["a digit".to_string()]));
+}
+
+#[test]
+fn parse_all() {
+    let parser = statement();
+
+    let chars = "12;".chars().collect::<Vec<_>>();
+    assert_eq!(parser.parse_all(&chars).unwrap_success(), 12);
+
+    let chars = "12;3".chars().collect::<Vec<_>>();
+    let failure = parser.parse_all(&chars).unwrap_failure();
+    assert_eq!(failure.message(), "unexpected trailing input");
+    assert_eq!(failure.furthest.index(), 3);
+    assert_eq!(failure.span(), Some(&ParsingRange::new(3.into(), 4.into())));
+
+    let chars = "12".chars().collect::<Vec<_>>();
+    let failure = parser.parse_all(&chars).unwrap_failure();
+    assert_eq!(failure.expected, Some(vec![";".to_string()]));
+}
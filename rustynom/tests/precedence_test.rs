@@ -0,0 +1,225 @@
+use std::{cell::Cell, rc::Rc};
+
+use rustynom::{
+    atomic_parsers::LiteralParserNoOutput,
+    combinator_parsers::{Associativity, PrecedenceParser, SameOrParser2, precedence},
+    parse_str,
+    parser::{ParserCombinator, ParserWrapper, Shared},
+    transformation_parsers::RecParser,
+    utility_parsers,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Num(u64),
+    Neg(Box<Expr>),
+    Fact(Box<Expr>),
+    Bin(char, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    fn bin(op: char) -> impl Fn(Expr, Expr) -> Expr {
+        move |a, b| Expr::Bin(op, Box::new(a), Box::new(b))
+    }
+}
+
+impl std::fmt::Display for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expr::Num(n) => write!(f, "{}", n),
+            Expr::Neg(e) => write!(f, "(-{})", e),
+            Expr::Fact(e) => write!(f, "({}!)", e),
+            Expr::Bin(op, a, b) => write!(f, "({} {} {})", a, op, b),
+        }
+    }
+}
+
+fn expression() -> ParserWrapper<char, Expr, true> {
+    let rec = RecParser::new(|expr| {
+        let atom = SameOrParser2::new(
+            utility_parsers::uint().map(Expr::Num),
+            expr.surround(
+                LiteralParserNoOutput::new('('),
+                LiteralParserNoOutput::new(')'),
+            ),
+        );
+
+        precedence(atom)
+            .infix(
+                LiteralParserNoOutput::new('+'),
+                1,
+                Associativity::Left,
+                Expr::bin('+'),
+            )
+            .infix(
+                LiteralParserNoOutput::new('-'),
+                1,
+                Associativity::Left,
+                Expr::bin('-'),
+            )
+            .infix(
+                LiteralParserNoOutput::new('*'),
+                2,
+                Associativity::Left,
+                Expr::bin('*'),
+            )
+            .infix(
+                LiteralParserNoOutput::new('^'),
+                4,
+                Associativity::Right,
+                Expr::bin('^'),
+            )
+            .prefix(LiteralParserNoOutput::new('-'), 3, |e| {
+                Expr::Neg(Box::new(e))
+            })
+            .postfix(LiteralParserNoOutput::new('!'), 5, |e| {
+                Expr::Fact(Box::new(e))
+            })
+    });
+
+    ParserWrapper::from_parser(rec.then_eof())
+}
+
+#[test]
+fn binary_operators() {
+    let parser = expression();
+
+    let result = parse_str!(parser, "1");
+    assert_eq!(result.unwrap_success(), Expr::Num(1));
+
+    let result = parse_str!(parser, "1-2-3");
+    assert_eq!(result.unwrap_success().to_string(), "((1 - 2) - 3)");
+
+    let result = parse_str!(parser, "1+2*3-4");
+    assert_eq!(result.unwrap_success().to_string(), "((1 + (2 * 3)) - 4)");
+
+    let result = parse_str!(parser, "2^3^2*2");
+    assert_eq!(result.unwrap_success().to_string(), "((2 ^ (3 ^ 2)) * 2)");
+
+    let result = parse_str!(parser, "(1+2)*3");
+    assert_eq!(result.unwrap_success().to_string(), "((1 + 2) * 3)");
+}
+
+#[test]
+fn unary_operators() {
+    let parser = expression();
+
+    let result = parse_str!(parser, "-1*2");
+    assert_eq!(result.unwrap_success().to_string(), "((-1) * 2)");
+
+    let result = parse_str!(parser, "-2^2");
+    assert_eq!(result.unwrap_success().to_string(), "(-(2 ^ 2))");
+
+    let result = parse_str!(parser, "--3!");
+    assert_eq!(result.unwrap_success().to_string(), "(-(-(3!)))");
+
+    let result = parse_str!(parser, "2!^2");
+    assert_eq!(result.unwrap_success().to_string(), "((2!) ^ 2)");
+}
+
+#[test]
+fn prefix_binding_power() {
+    // a prefix operator binds tighter than an infix operator of the same binding power
+    let parser = ParserWrapper::<char, Expr, true>::from_parser(
+        precedence(utility_parsers::uint().map(Expr::Num))
+            .infix(
+                LiteralParserNoOutput::new('-'),
+                1,
+                Associativity::Left,
+                Expr::bin('-'),
+            )
+            .prefix(LiteralParserNoOutput::new('-'), 1, |e| {
+                Expr::Neg(Box::new(e))
+            })
+            .then_eof(),
+    );
+
+    let result = parse_str!(parser, "-1-2");
+    assert_eq!(result.unwrap_success().to_string(), "((-1) - 2)");
+
+    let result = parse_str!(parser, "--1-2");
+    assert_eq!(result.unwrap_success().to_string(), "((-(-1)) - 2)");
+}
+
+#[test]
+fn zero_width_operators() {
+    let parser = ParserWrapper::<char, Expr, true>::from_parser(
+        precedence(utility_parsers::uint().map(Expr::Num))
+            .prefix(
+                LiteralParserNoOutput::new('-').optional().map(|_| ()),
+                1,
+                |e| Expr::Neg(Box::new(e)),
+            )
+            .then_eof(),
+    );
+    let failure = parse_str!(parser, "1").unwrap_failure();
+    assert!(failure.committed);
+    assert_eq!(failure.furthest.index(), 0);
+
+    let parser = ParserWrapper::<char, Expr, true>::from_parser(
+        precedence(utility_parsers::uint().map(Expr::Num))
+            .postfix(
+                LiteralParserNoOutput::new('!').optional().map(|_| ()),
+                1,
+                |e| Expr::Fact(Box::new(e)),
+            )
+            .then_eof(),
+    );
+    let failure = parse_str!(parser, "1").unwrap_failure();
+    assert!(failure.committed);
+    assert_eq!(failure.furthest.index(), 1);
+}
+
+#[test]
+fn precedence_failures() {
+    let parser = expression();
+
+    let result = parse_str!(parser, "1+");
+    let failure = result.unwrap_failure();
+    assert_eq!(failure.furthest.index(), 2);
+    assert_eq!(failure.expected_items(), vec!["(", "-", "a digit"]);
+
+    let result = parse_str!(parser, "(1+2");
+    let failure = result.unwrap_failure();
+    assert_eq!(failure.furthest.index(), 4);
+}
+
+#[test]
+fn operator_sharing() {
+    // operators that count their uses through an `Rc` are fine in a local parser
+    let uses = Rc::new(Cell::new(0));
+    let counted = uses.clone();
+    let parser = ParserWrapper::<char, u64, true>::from_parser(
+        precedence(utility_parsers::uint())
+            .infix(
+                LiteralParserNoOutput::new('+'),
+                1,
+                Associativity::Left,
+                move |a, b| {
+                    counted.set(counted.get() + 1);
+                    a + b
+                },
+            )
+            .then_eof(),
+    );
+    assert_eq!(parse_str!(parser, "1+2+3").unwrap_success(), 6);
+    assert_eq!(uses.get(), 2);
+
+    let parser = ParserWrapper::<char, u64, true, Shared>::from_parser_sync(
+        PrecedenceParser::new_sync(utility_parsers::uint())
+            .infix(
+                LiteralParserNoOutput::new('*'),
+                1,
+                Associativity::Left,
+                |a, b| a * b,
+            )
+            .then_eof(),
+    );
+    let product = std::thread::scope(|scope| {
+        scope
+            .spawn(|| parse_str!(parser, "2*3*4").unwrap_success())
+            .join()
+            .unwrap()
+    });
+    assert_eq!(product, 24);
+}
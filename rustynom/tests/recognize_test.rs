@@ -0,0 +1,75 @@
+use rustynom::{
+    ParsingPosition,
+    atomic_parsers::{LiteralParser, LiteralParserNoOutput},
+    combinator_parsers::AndParser2,
+    context::ParseContext,
+    parsable::Utf8,
+    parser::{ParserCombinator, ParserWrapper, RawTestParser},
+    transformation_parsers::OptionalParser,
+    utility_parsers,
+};
+
+#[test]
+fn simple_recognize() {
+    let digits = utility_parsers::multi_test(|c: &char| c.is_ascii_digit(), "digits".to_string());
+    let parser = AndParser2::new(
+        digits.clone(),
+        OptionalParser::new(LiteralParser::new('.').and(digits)),
+    )
+    .recognize();
+
+    let chars = "12.50x".chars().collect::<Vec<_>>();
+    let mut position = ParsingPosition::default();
+    let result = RawTestParser::<char, true>::parse(
+        &parser,
+        &chars,
+        &mut position,
+        &mut ParseContext::new(),
+    );
+    assert_eq!(result.unwrap_success(), &chars[0..5]);
+    assert_eq!(position.index(), 5);
+
+    let chars = "x".chars().collect::<Vec<_>>();
+    let result = RawTestParser::<char, true>::parse(
+        &parser,
+        &chars,
+        &mut ParsingPosition::default(),
+        &mut ParseContext::new(),
+    );
+    assert_eq!(
+        result.unwrap_failure().expected,
+        Some(vec!["digits".to_string()])
+    );
+}
+
+#[test]
+fn slice_parsers() {
+    let parser = utility_parsers::multi_test_slice::<Utf8, _>(
+        |b: &u8| b.is_ascii_lowercase(),
+        "a word".to_string(),
+    )
+    .skip(LiteralParserNoOutput::new(b' '))
+    .and(utility_parsers::remaining_slice::<Utf8>());
+
+    let input = b"hello world";
+    let result = RawTestParser::<Utf8, true>::parse(
+        &parser,
+        input,
+        &mut ParsingPosition::default(),
+        &mut ParseContext::new(),
+    );
+    assert_eq!(result.unwrap_success(), (&b"hello"[..], &b"world"[..]));
+}
+
+#[test]
+fn recognize_in_wrapper() {
+    let parser = ParserWrapper::<char, usize, true>::from_parser(
+        utility_parsers::multi_test(|c: &char| c.is_ascii_digit(), "digits".to_string())
+            .separated_by(LiteralParserNoOutput::new(','))
+            .recognize()
+            .map(<[char]>::len),
+    );
+
+    let chars = "1,23,456".chars().collect::<Vec<_>>();
+    assert_eq!(parser.parse_slice(&chars).unwrap_success(), 8);
+}
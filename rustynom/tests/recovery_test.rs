@@ -0,0 +1,165 @@
+use rustynom::{
+    atomic_parsers::{LiteralParser, LiteralParserNoOutput},
+    combinator_parsers::{AndParser3, AndParser4},
+    parse_str,
+    parser::{ParserCombinator, ParserWrapper},
+    recovery_parsers::{recover_nested, skip_until},
+    transformation_parsers::ManyNonEmptyParser,
+    utility_parsers,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Stmt {
+    Assign(char, u64),
+    Error,
+}
+
+#[test]
+fn simple_skip_until() {
+    let assign = AndParser4::new(
+        utility_parsers::test(|c: &char| c.is_ascii_alphabetic(), "a letter".to_string()),
+        LiteralParserNoOutput::new('='),
+        utility_parsers::uint(),
+        LiteralParserNoOutput::new(';'),
+    )
+    .map(|(name, _, value, _)| Stmt::Assign(name, value))
+    .recover_with(skip_until(';').map(|_| Stmt::Error));
+
+    let parser = ParserWrapper::<char, Vec<Stmt>, true>::from_parser(
+        ManyNonEmptyParser::new(assign).then_eof(),
+    );
+
+    let chars = "a=1;b=x;c=;d=4;".chars().collect::<Vec<_>>();
+    let (output, errors) = parser.parse_slice_recovering(&chars);
+    assert_eq!(
+        output,
+        Some(vec![
+            Stmt::Assign('a', 1),
+            Stmt::Error,
+            Stmt::Error,
+            Stmt::Assign('d', 4),
+        ])
+    );
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].furthest.index(), 6);
+    assert_eq!(errors[0].expected, Some(vec!["a digit".to_string()]));
+    assert_eq!(errors[1].furthest.index(), 10);
+
+    let result = parse_str!(parser, "a=1;b=2;");
+    assert!(result.is_success());
+}
+
+#[test]
+fn recovery_fails() {
+    let assign = AndParser4::new(
+        utility_parsers::test(|c: &char| c.is_ascii_alphabetic(), "a letter".to_string()),
+        LiteralParserNoOutput::new('='),
+        utility_parsers::uint(),
+        LiteralParserNoOutput::new(';'),
+    )
+    .map(|(name, _, value, _)| Stmt::Assign(name, value))
+    .recover_with(skip_until(';').map(|_| Stmt::Error));
+
+    let parser = ParserWrapper::<char, Stmt, true>::from_parser(assign);
+
+    // there is no ';' to skip to, so the original failure is returned
+    let chars = "a=x".chars().collect::<Vec<_>>();
+    let (output, errors) = parser.parse_slice_recovering(&chars);
+    assert_eq!(output, None);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].furthest.index(), 2);
+}
+
+#[test]
+fn simple_recover_nested() {
+    let block = LiteralParser::new('a')
+        .many_non_empty()
+        .surround(
+            LiteralParserNoOutput::new('{'),
+            LiteralParserNoOutput::new('}'),
+        )
+        .map(|a| a.len())
+        .recover_with(recover_nested('{', '}').map(|_| 0));
+
+    let parser = ParserWrapper::<char, Vec<usize>, true>::from_parser(
+        block.separated_by(LiteralParserNoOutput::new(',')),
+    );
+
+    let chars = "{aa},{a{b}a},{a}".chars().collect::<Vec<_>>();
+    let (output, errors) = parser.parse_slice_recovering(&chars);
+    assert_eq!(output, Some(vec![2, 0, 1]));
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].furthest.index(), 7);
+}
+
+#[test]
+fn abandoned_iteration_drops_diagnostics() {
+    // item = 'a' (uint ';' | recover) 'b', the recovery succeeds but the item fails on 'b'
+    let item = || {
+        AndParser3::new(
+            LiteralParserNoOutput::new('a'),
+            utility_parsers::uint()
+                .skip(LiteralParserNoOutput::new(';'))
+                .recover_with(skip_until(';').map(|_| 0)),
+            LiteralParserNoOutput::new('b'),
+        )
+        .map(|(_, n, _)| n)
+    };
+    let chars = "ax;c".chars().collect::<Vec<_>>();
+
+    let parser = ParserWrapper::<char, (Vec<u64>, String), true>::from_parser(
+        item().many().and(utility_parsers::remaining::<char>()),
+    );
+    let (output, errors) = parser.parse_slice_recovering(&chars);
+    assert_eq!(output, Some((vec![], "ax;c".to_string())));
+    assert_eq!(errors, vec![]);
+
+    let parser = ParserWrapper::<char, (Option<u64>, String), true>::from_parser(
+        item().optional().and(utility_parsers::remaining::<char>()),
+    );
+    let (output, errors) = parser.parse_slice_recovering(&chars);
+    assert_eq!(output, Some((None, "ax;c".to_string())));
+    assert_eq!(errors, vec![]);
+
+    let parser = ParserWrapper::<char, (Vec<u64>, String), true>::from_parser(
+        item().at_most(2).and(utility_parsers::remaining::<char>()),
+    );
+    let (output, errors) = parser.parse_slice_recovering(&chars);
+    assert_eq!(output, Some((vec![], "ax;c".to_string())));
+    assert_eq!(errors, vec![]);
+}
+
+#[test]
+fn abandoned_separator_drops_diagnostics() {
+    // the same item as above, as the separator of a list and as its elements
+    let item = || {
+        AndParser3::new(
+            LiteralParserNoOutput::new('a'),
+            utility_parsers::uint()
+                .skip(LiteralParserNoOutput::new(';'))
+                .recover_with(skip_until(';').map(|_| 0)),
+            LiteralParserNoOutput::new('b'),
+        )
+        .map(|(_, n, _)| n)
+    };
+
+    let parser = ParserWrapper::<char, (Vec<char>, String), true>::from_parser(
+        LiteralParser::new('x')
+            .separated_by(item().map(|_| ()))
+            .and(utility_parsers::remaining::<char>()),
+    );
+    let chars = "xax;c".chars().collect::<Vec<_>>();
+    let (output, errors) = parser.parse_slice_recovering(&chars);
+    assert_eq!(output, Some((vec!['x'], "ax;c".to_string())));
+    assert_eq!(errors, vec![]);
+
+    let parser = ParserWrapper::<char, (Vec<u64>, String), true>::from_parser(
+        item()
+            .separated_by(LiteralParserNoOutput::new(','))
+            .and(utility_parsers::remaining::<char>()),
+    );
+    let chars = "a1;b,ax;c".chars().collect::<Vec<_>>();
+    let (output, errors) = parser.parse_slice_recovering(&chars);
+    assert_eq!(output, Some((vec![1], ",ax;c".to_string())));
+    assert_eq!(errors, vec![]);
+}
@@ -1,9 +1,12 @@
+use std::{rc::Rc, sync::LazyLock};
+
 use rustynom::{
     atomic_parsers::{EofParser, LiteralListParser, LiteralParserNoOutput},
-    combinator_parsers::{AndParser2, SameOrParser2},
+    combinator_parsers::{AndParser2, AndParser3, SameOrParser2, SameOrParser3},
     parse_str,
-    parser::{ParserCombinator, ParserWrapper},
-    transformation_parsers::RecParser,
+    parser::{Input, Local, ParserCombinator, ParserWrapper, Shared},
+    transformation_parsers::{RecParser, RecRefParser},
+    utility_parsers,
 };
 
 #[test]
@@ -126,3 +129,200 @@ fn simple_recursion_3() {
     let result = parse_str!(p, "[b]");
     assert!(result.is_failure());
 }
+
+#[test]
+fn left_recursion() {
+    // expr = expr '-' digit | expr '+' digit | digit
+    let digit = utility_parsers::test(|c: &char| c.is_ascii_digit(), "a digit".to_string())
+        .map(|c: char| c.to_digit(10).unwrap() as i64);
+
+    let rec = RecParser::new(|expr| {
+        SameOrParser3::new(
+            AndParser3::new(expr.clone(), LiteralParserNoOutput::new('-'), digit.clone())
+                .map(|(a, _, b)| a - b),
+            AndParser3::new(expr, LiteralParserNoOutput::new('+'), digit.clone())
+                .map(|(a, _, b)| a + b),
+            digit,
+        )
+    });
+
+    let p = ParserWrapper::<char, i64, true>::from_parser(rec.then_eof());
+
+    let result = parse_str!(p, "7");
+    assert_eq!(result.unwrap_success(), 7);
+
+    // left associative, (((9 - 2) - 3) + 1)
+    let result = parse_str!(p, "9-2-3+1");
+    assert_eq!(result.unwrap_success(), 5);
+
+    // the longest expression is "9-2", the dangling '-' is left for `then_eof`
+    let result = parse_str!(p, "9-2-");
+    let failure = result.unwrap_failure();
+    assert_eq!(failure.furthest.index(), 3);
+    assert_eq!(failure.expected, Some(vec!["EOF".to_string()]));
+}
+
+#[test]
+fn left_recursion_through_map() {
+    // list = item ',' 'a' | 'a', item = list
+    let rec = RecParser::new(|list| {
+        let item = list.map(|n: usize| n);
+        SameOrParser2::new(
+            AndParser2::new(
+                item.skip(LiteralParserNoOutput::new(',')),
+                LiteralParserNoOutput::new('a'),
+            )
+            .map(|(n, _)| n + 1),
+            LiteralParserNoOutput::new('a').map(|_| 1),
+        )
+    });
+
+    let p = ParserWrapper::<char, usize>::from_parser(rec.then_eof());
+
+    let result = parse_str!(p, "a,a,a");
+    assert_eq!(result.unwrap_success(), 3);
+}
+
+#[test]
+fn indirect_left_recursion() {
+    // a = b 'x' | 'a', b = a 'y' | 'b'
+    let a = RecRefParser::<char, String, true>::new();
+    let b = RecRefParser::<char, String, true>::new();
+    a.set(Box::new(SameOrParser2::new(
+        b.clone()
+            .skip(LiteralParserNoOutput::new('x'))
+            .map(|b: String| format!("({}x)", b)),
+        LiteralListParser::new("a".to_string()),
+    )));
+    b.set(Box::new(SameOrParser2::new(
+        a.clone()
+            .skip(LiteralParserNoOutput::new('y'))
+            .map(|a: String| format!("({}y)", a)),
+        LiteralListParser::new("b".to_string()),
+    )));
+
+    let p = ParserWrapper::<char, String, true>::from_parser(a.clone().then_eof());
+    assert_eq!(parse_str!(p, "a").unwrap_success(), "a");
+    assert_eq!(parse_str!(p, "ayx").unwrap_success(), "((ay)x)");
+    assert_eq!(parse_str!(p, "bxyx").unwrap_success(), "(((bx)y)x)");
+    assert!(parse_str!(p, "ay").is_failure());
+
+    // entering the cycle through the other rule
+    let p = ParserWrapper::<char, String, true>::from_parser(b.then_eof());
+    assert_eq!(parse_str!(p, "ay").unwrap_success(), "(ay)");
+    assert_eq!(parse_str!(p, "bxy").unwrap_success(), "((bx)y)");
+}
+
+static NESTING: LazyLock<ParserWrapper<char, usize, true, Shared>> = LazyLock::new(|| {
+    // nesting = '[' nesting ']' | 'a'
+    let rec = RecParser::new_sync(|nesting| {
+        SameOrParser2::new(
+            nesting
+                .surround(
+                    LiteralParserNoOutput::new('['),
+                    LiteralParserNoOutput::new(']'),
+                )
+                .map(|depth: usize| depth + 1),
+            LiteralParserNoOutput::new('a').map(|_| 0),
+        )
+    });
+
+    ParserWrapper::from_parser_sync(rec.then_eof())
+});
+
+#[test]
+fn shared_across_threads() {
+    std::thread::scope(|scope| {
+        let handles = (0..4)
+            .map(|depth| {
+                scope.spawn(move || {
+                    let input = format!("{}a{}", "[".repeat(depth), "]".repeat(depth));
+                    let chars = input.chars().collect::<Vec<_>>();
+                    NESTING.parse_slice(&chars).unwrap_success()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for (depth, handle) in handles.into_iter().enumerate() {
+            assert_eq!(handle.join().unwrap(), depth);
+        }
+    });
+}
+
+#[test]
+fn local_grammar() {
+    // a grammar that captures an `Rc` is not `Send`, it only needs the local wrappers
+    let names = Rc::new(vec!["a".to_string(), "b".to_string()]);
+    let rec = RecParser::new(|list| {
+        let names = names.clone();
+        SameOrParser2::new(
+            list.surround(
+                LiteralParserNoOutput::new('('),
+                LiteralParserNoOutput::new(')'),
+            ),
+            utility_parsers::test(|c: &char| c.is_ascii_digit(), "a digit".to_string())
+                .map(move |c: char| names[c.to_digit(10).unwrap() as usize].clone()),
+        )
+    });
+    let parser = ParserWrapper::<char, String, true>::from_parser(rec.then_eof());
+
+    assert_eq!(parse_str!(parser, "((1))").unwrap_success(), "b");
+}
+
+#[test]
+fn borrowed_left_recursion() {
+    // path = path '.' name | name, with the names borrowed from the input
+    let name = || {
+        utility_parsers::multi_test(|c: &char| c.is_ascii_lowercase(), "a name".to_string())
+            .recognize()
+    };
+
+    let chars = "a.bc.d".chars().collect::<Vec<_>>();
+    let rec = RecParser::new_borrowed(|path| {
+        SameOrParser2::new(
+            AndParser3::new(path, LiteralParserNoOutput::new('.'), name()).map(
+                |(mut path, _, name): (Vec<&[char]>, _, _)| {
+                    path.push(name);
+                    path
+                },
+            ),
+            name().map(|name| vec![name]),
+        )
+    });
+    let parser = ParserWrapper::<char, Vec<&[char]>, true, Local, Input>::from_parser_borrowed(
+        rec.then_eof(),
+    );
+
+    assert_eq!(
+        parser.parse_slice(&chars).unwrap_success(),
+        vec![&chars[0..1], &chars[2..4], &chars[5..6]]
+    );
+}
+
+#[test]
+fn shared_borrowed_grammar() {
+    let name = || {
+        utility_parsers::multi_test(|c: &char| c.is_ascii_lowercase(), "a name".to_string())
+            .recognize()
+    };
+
+    let chars = "a.bc".chars().collect::<Vec<_>>();
+    let rec = RecParser::new_borrowed_sync(|path| {
+        SameOrParser2::new(
+            AndParser3::new(path, LiteralParserNoOutput::new('.'), name()).map(
+                |(mut path, _, name): (Vec<&[char]>, _, _)| {
+                    path.push(name);
+                    path
+                },
+            ),
+            name().map(|name| vec![name]),
+        )
+    });
+    let parser =
+        ParserWrapper::<char, Vec<&[char]>, true, Shared, Input>::from_parser_borrowed_sync(
+            rec.then_eof(),
+        );
+
+    let result = std::thread::scope(|s| s.spawn(|| parser.parse_slice(&chars)).join().unwrap());
+    assert_eq!(result.unwrap_success(), vec![&chars[0..1], &chars[2..4]]);
+}
@@ -0,0 +1,117 @@
+use rustynom::{
+    atomic_parsers::LiteralParserNoOutput,
+    parse_str,
+    parser::{ParserCombinator, ParserWrapper},
+    utility_parsers,
+};
+
+fn hex_digit() -> impl for<'a> ParserCombinator<'a, char, true, TOut = char> {
+    utility_parsers::test(|c: &char| c.is_ascii_hexdigit(), "a hex digit".to_string())
+}
+
+#[test]
+fn repeat() {
+    let parser =
+        ParserWrapper::<char, Vec<char>, true>::from_parser(hex_digit().repeat(3).then_eof());
+
+    assert_eq!(
+        parse_str!(parser, "a1f").unwrap_success(),
+        vec!['a', '1', 'f']
+    );
+
+    let failure = parse_str!(parser, "a1").unwrap_failure();
+    assert_eq!(failure.furthest.index(), 2);
+    assert_eq!(failure.message(), "expected one of: 3 items, a hex digit");
+
+    let failure = parse_str!(parser, "a1f0").unwrap_failure();
+    assert_eq!(failure.expected, Some(vec!["EOF".to_string()]));
+}
+
+#[test]
+fn at_least_and_at_most() {
+    let parser =
+        ParserWrapper::<char, Vec<char>, true>::from_parser(hex_digit().at_least(3).then_eof());
+
+    assert_eq!(parse_str!(parser, "12345").unwrap_success().len(), 5);
+    let failure = parse_str!(parser, "12").unwrap_failure();
+    assert_eq!(
+        failure.message(),
+        "expected one of: a hex digit, at least 3 items"
+    );
+
+    let parser = ParserWrapper::<char, Vec<char>, true>::from_parser(hex_digit().at_most(2));
+
+    assert_eq!(parse_str!(parser, "").unwrap_success(), vec![]);
+    assert_eq!(parse_str!(parser, "123").unwrap_success(), vec!['1', '2']);
+}
+
+#[test]
+fn repeat_range() {
+    let parser = ParserWrapper::<char, String, true>::from_parser(
+        hex_digit()
+            .repeat_range(2..=8)
+            .map(|digits| digits.into_iter().collect())
+            .then_eof(),
+    );
+
+    assert_eq!(parse_str!(parser, "ff").unwrap_success(), "ff");
+    assert_eq!(parse_str!(parser, "deadbeef").unwrap_success(), "deadbeef");
+    assert!(parse_str!(parser, "f").is_failure());
+    assert!(parse_str!(parser, "deadbeef0").is_failure());
+
+    let parser =
+        ParserWrapper::<char, Vec<char>, true>::from_parser(hex_digit().repeat_range(1..3));
+    assert_eq!(parse_str!(parser, "123").unwrap_success(), vec!['1', '2']);
+    assert_eq!(
+        parse_str!(parser, "").unwrap_failure().message(),
+        "expected one of: a hex digit, at least 1 item"
+    );
+}
+
+#[test]
+fn repeat_failures() {
+    // the failure of an item that got further is kept
+    let pair = hex_digit().and(hex_digit());
+    let parser =
+        ParserWrapper::<char, Vec<(char, char)>, true>::from_parser(pair.repeat(2).then_eof());
+    let failure = parse_str!(parser, "ff0").unwrap_failure();
+    assert_eq!(failure.furthest.index(), 3);
+    assert_eq!(failure.expected, Some(vec!["a hex digit".to_string()]));
+
+    // a huge minimum does not allocate up front
+    let parser =
+        ParserWrapper::<char, Vec<char>, true>::from_parser(hex_digit().repeat(usize::MAX));
+    let failure = parse_str!(parser, "ff").unwrap_failure();
+    assert_eq!(failure.furthest.index(), 2);
+
+    // bounded repetitions need progress too
+    let parser = ParserWrapper::<char, Vec<Option<char>>, true>::from_parser(
+        hex_digit().optional().repeat(3),
+    );
+    let failure = parse_str!(parser, "f").unwrap_failure();
+    assert!(failure.committed);
+    assert_eq!(failure.furthest.index(), 1);
+}
+
+#[test]
+fn repeat_array() {
+    let octet = utility_parsers::uint().map(|n| n as u8);
+    let parser = ParserWrapper::<char, [u8; 4], true>::from_parser(
+        octet
+            .clone()
+            .and(
+                LiteralParserNoOutput::new('.')
+                    .and(octet)
+                    .map(|(_, n)| n)
+                    .repeat_array::<3>(),
+            )
+            .map(|(first, [b, c, d])| [first, b, c, d])
+            .then_eof(),
+    );
+
+    assert_eq!(
+        parse_str!(parser, "192.168.0.1").unwrap_success(),
+        [192, 168, 0, 1]
+    );
+    assert!(parse_str!(parser, "192.168.0").is_failure());
+}
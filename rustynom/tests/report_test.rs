@@ -0,0 +1,103 @@
+use rustynom::{
+    ParseFailure, ParsingRange,
+    atomic_parsers::{LiteralParser, LiteralParserNoOutput},
+    combinator_parsers::{AndParser2, OrParser2},
+    parsable::Utf8,
+    parse_str,
+    parser::ParserWrapper,
+    transformation_parsers::ManyParser,
+};
+
+#[test]
+fn simple_report() {
+    let parser = ParserWrapper::<char, _, true>::from_parser(AndParser2::new(
+        ManyParser::new(LiteralParserNoOutput::new('\n')),
+        OrParser2::new(LiteralParser::new('a'), LiteralParser::new('b')),
+    ));
+
+    let result = parse_str!(parser, "\n\nc");
+    assert!(result.is_failure());
+
+    let chars = "\n\nc".chars().collect::<Vec<_>>();
+    let failure = result.unwrap_failure();
+    assert_eq!(failure.message(), "expected one of: a, b");
+    assert_eq!(
+        failure.report::<char>(&chars).to_string(),
+        "error: expected one of: a, b\n --> 3:1\n  |\n3 | c\n  | ^"
+    );
+}
+
+#[test]
+fn report_caret_position() {
+    let chars = "let x = ;".chars().collect::<Vec<_>>();
+    let failure = ParseFailure::new(
+        8.into(),
+        Some(vec![
+            "a number".to_string(),
+            "an identifier".to_string(),
+            "a number".to_string(),
+        ]),
+    );
+
+    assert_eq!(failure.expected_items(), vec!["a number", "an identifier"]);
+    assert_eq!(
+        failure.report::<char>(&chars).to_string(),
+        "error: expected one of: a number, an identifier\n --> 1:9\n  |\n1 | let x = ;\n  |         ^"
+    );
+}
+
+#[test]
+fn report_colored() {
+    let chars = "x".chars().collect::<Vec<_>>();
+    let failure = ParseFailure::new(1.into(), None);
+
+    assert_eq!(failure.to_string(), "parsing failed at index 1");
+
+    let report = failure.report::<char>(&chars).colored(true).to_string();
+    assert!(report.starts_with("\x1b[1;31merror\x1b[0m: parsing failed"));
+    assert!(report.ends_with("\x1b[1;31m^\x1b[0m"));
+}
+
+#[test]
+fn report_utf8_line() {
+    let bytes = "héllo wörld!".as_bytes();
+
+    // "!" is at byte 13 but only 11 characters come before it on the line
+    let failure = ParseFailure::new(13.into(), Some(vec!["a letter".to_string()]));
+    assert_eq!(
+        failure.report::<Utf8>(bytes).to_string(),
+        "error: expected a letter\n --> 1:12\n  |\n1 | héllo wörld!\n  |            ^"
+    );
+
+    // the underline counts characters, not bytes
+    let failure = ParseFailure::with_error(
+        ParsingRange::new(7.into(), 13.into()),
+        "unknown word".to_string(),
+    );
+    assert_eq!(
+        failure.report::<Utf8>(bytes).to_string(),
+        "error: unknown word\n --> 1:7\n  |\n1 | héllo wörld!\n  |       ^^^^^"
+    );
+}
+
+#[test]
+fn report_crlf_line() {
+    let chars = "ab\r\ncd".chars().collect::<Vec<_>>();
+    let failure = ParseFailure::new(1.into(), None);
+
+    assert_eq!(
+        failure.report::<char>(&chars).to_string(),
+        "error: parsing failed\n --> 1:2\n  |\n1 | ab\n  |  ^"
+    );
+}
+
+#[test]
+fn report_bytes() {
+    let bytes = [0x01, 0x02, 0x03];
+    let failure = ParseFailure::new(1.into(), Some(vec!["0x00".to_string()]));
+
+    assert_eq!(
+        failure.report::<u8>(&bytes).to_string(),
+        "error: expected 0x00\n --> 1:2\n  |\n1 | 0x01 0x02 0x03\n  |      ^"
+    );
+}
@@ -0,0 +1,55 @@
+use rustynom::{
+    ParsingRange, Spanned,
+    atomic_parsers::LiteralParserNoOutput,
+    parse_str,
+    parser::{ParserCombinator, ParserWrapper},
+    utility_parsers,
+};
+
+fn range(from: usize, to: usize) -> ParsingRange {
+    ParsingRange::new(from.into(), to.into())
+}
+
+#[test]
+fn spanned() {
+    let parser = ParserWrapper::<char, Spanned<u64>, true>::from_parser(
+        utility_parsers::uint()
+            .spanned()
+            .trim(utility_parsers::optional_whitespace()),
+    );
+
+    let result = parse_str!(parser, "  123 ").unwrap_success();
+    assert_eq!(result, Spanned::new(123, range(2, 5)));
+    assert_eq!(result.map(|n| n * 2).value, 246);
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Number(u64),
+    Sum(Box<Spanned<Expr>>, Box<Spanned<Expr>>),
+}
+
+fn sum(a: Spanned<Expr>, b: Spanned<Expr>) -> Spanned<Expr> {
+    a.clone()
+        .merge(b.clone(), |_, _| Expr::Sum(Box::new(a), Box::new(b)))
+}
+
+#[test]
+fn merge() {
+    let number = utility_parsers::uint().map(Expr::Number).spanned();
+    let plus = LiteralParserNoOutput::new('+').map(|_| sum);
+    let parser =
+        ParserWrapper::<char, Spanned<Expr>, true>::from_parser(number.chain_left(plus).then_eof());
+
+    let result = parse_str!(parser, "1+22+3").unwrap_success();
+    assert_eq!(result.range, range(0, 6));
+
+    let Expr::Sum(left, right) = result.value else {
+        panic!("expected a sum");
+    };
+    assert_eq!(left.range, range(0, 4));
+    assert_eq!(*right, Spanned::new(Expr::Number(3), range(5, 6)));
+
+    let merged = Spanned::new(1, range(3, 5)).merge(Spanned::new(2, range(1, 2)), |a, b| a + b);
+    assert_eq!(merged, Spanned::new(3, range(1, 5)));
+}
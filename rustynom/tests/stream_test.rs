@@ -0,0 +1,243 @@
+use std::{
+    cell::Cell,
+    io::{self, Read},
+    rc::Rc,
+};
+
+use rustynom::{
+    ParseResult, ParsingPosition,
+    atomic_parsers::{
+        EofParser, LiteralListMapParser, LiteralListParser, LiteralListParserNoOutput,
+        LiteralParserNoOutput,
+    },
+    binary_parsers,
+    combinator_parsers::SameOrParser2,
+    context::ParseContext,
+    parsable::Utf8,
+    parser::{ParserCombinator, ParserWrapper, RawTestParser},
+    stream::{StreamError, StreamParser},
+    utility_parsers,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Command {
+    Ping,
+    Echo(String),
+}
+
+// command = "PING\n" | "ECHO " text "\n"
+fn command_parser() -> impl for<'a> ParserCombinator<'a, Utf8, true, TOut = Command> {
+    let echo = utility_parsers::multi_char_test::<Utf8, _>(|c| c != '\n', "text".to_string())
+        .surround(
+            LiteralListParserNoOutput::new("ECHO ".to_string()),
+            LiteralParserNoOutput::new(b'\n'),
+        )
+        .map(Command::Echo);
+
+    SameOrParser2::new(
+        LiteralListMapParser::new("PING\n".to_string(), Command::Ping),
+        echo,
+    )
+}
+
+fn command() -> ParserWrapper<Utf8, Command, true> {
+    ParserWrapper::from_parser(command_parser())
+}
+
+/// Counts how often the parser runs.
+#[derive(Clone)]
+struct Counted<P> {
+    parser: P,
+    parses: Rc<Cell<usize>>,
+}
+
+impl<'a, P: RawTestParser<'a, Utf8, true>> RawTestParser<'a, Utf8, true> for Counted<P> {
+    type TOut = P::TOut;
+
+    fn parse(
+        &self,
+        input: &'a [u8],
+        position: &mut ParsingPosition,
+        context: &mut ParseContext<'a>,
+    ) -> ParseResult<P::TOut> {
+        self.parses.set(self.parses.get() + 1);
+        self.parser.parse(input, position, context)
+    }
+}
+
+fn chunks(input: &[u8], sizes: &[usize]) -> Vec<Vec<u8>> {
+    let mut rest = input;
+    let mut chunks = Vec::new();
+    for size in sizes {
+        let (chunk, tail) = rest.split_at(*size);
+        chunks.push(chunk.to_vec());
+        rest = tail;
+    }
+    chunks.push(rest.to_vec());
+    chunks
+}
+
+#[test]
+fn incomplete_is_not_eof() {
+    let input = "PI".as_bytes();
+
+    let mut context = ParseContext::new();
+    context.set_partial(true);
+    let result = RawTestParser::<Utf8, true>::parse(
+        &LiteralListParser::new("PING".to_string()),
+        input,
+        &mut ParsingPosition::default(),
+        &mut context,
+    );
+    let failure = result.unwrap_failure();
+    assert_eq!(failure.incomplete(), Some(2));
+    assert_eq!(failure.furthest.index(), 2);
+
+    let result = RawTestParser::<Utf8, true>::parse(
+        &EofParser::new(),
+        input,
+        &mut ParsingPosition::new(2),
+        &mut context,
+    );
+    assert_eq!(result.unwrap_failure().incomplete(), Some(1));
+
+    // without partial mode the end of the buffer is the end of the input
+    let result = RawTestParser::<Utf8, true>::parse(
+        &EofParser::new(),
+        input,
+        &mut ParsingPosition::new(2),
+        &mut ParseContext::new(),
+    );
+    assert!(result.is_success());
+}
+
+#[test]
+fn stream_chunks() {
+    let parser = command();
+    let input = "PING\nECHO héllo\nPING\n".as_bytes();
+
+    // split inside "PING", inside the two bytes of "é" and right after a newline
+    let chunks = chunks(input, &[2, 5, 4, 5]);
+    let commands = StreamParser::new(&parser, chunks)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(
+        commands,
+        vec![
+            Command::Ping,
+            Command::Echo("héllo".to_string()),
+            Command::Ping
+        ]
+    );
+}
+
+/// Hands out one byte per read, like a slow connection.
+struct Trickle<'a>(&'a [u8]);
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.0.split_first() {
+            Some((byte, rest)) if !buf.is_empty() => {
+                buf[0] = *byte;
+                self.0 = rest;
+                Ok(1)
+            }
+            _ => Ok(0),
+        }
+    }
+}
+
+#[test]
+fn stream_reader() {
+    let parser = command();
+    let reader = Trickle("ECHO a\nPING\nECHO b\n".as_bytes());
+
+    let commands = StreamParser::from_reader(&parser, reader)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(
+        commands,
+        vec![
+            Command::Echo("a".to_string()),
+            Command::Ping,
+            Command::Echo("b".to_string())
+        ]
+    );
+}
+
+#[test]
+fn stream_long_record() {
+    // an echo spanning many chunks is not parsed again for every chunk
+    let text = "x".repeat(64 * 1024);
+    let input = format!("PING\nECHO {}\nPING\n", text);
+    let pulled = Cell::new(0);
+    let chunks = input
+        .as_bytes()
+        .chunks(1024)
+        .map(<[u8]>::to_vec)
+        .inspect(|_| pulled.set(pulled.get() + 1));
+
+    let parses = Rc::new(Cell::new(0));
+    let parser = ParserWrapper::<Utf8, Command, true>::from_parser(Counted {
+        parser: command_parser(),
+        parses: parses.clone(),
+    });
+    let commands = StreamParser::new(&parser, chunks)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(
+        commands,
+        vec![Command::Ping, Command::Echo(text), Command::Ping]
+    );
+    assert_eq!(pulled.get(), input.len().div_ceil(1024));
+    assert!(parses.get() < 16, "parsed {} times", parses.get());
+}
+
+#[test]
+fn stream_failures() {
+    let parser = command();
+
+    // the last line is never terminated
+    let mut stream = StreamParser::new(&parser, chunks("PING\nECHO a".as_bytes(), &[7]));
+    assert_eq!(stream.next().unwrap().unwrap(), Command::Ping);
+    let Some(Err(StreamError::Parse(failure))) = stream.next() else {
+        panic!("expected a parse error");
+    };
+    assert_eq!(failure.furthest.index(), 11);
+    assert_eq!(failure.incomplete(), None);
+    assert!(stream.next().is_none());
+
+    // positions are relative to the start of the stream
+    let mut stream = StreamParser::new(&parser, chunks("PING\nPING\nPONG\n".as_bytes(), &[3, 8]));
+    assert_eq!(stream.next().unwrap().unwrap(), Command::Ping);
+    assert_eq!(stream.next().unwrap().unwrap(), Command::Ping);
+    assert_eq!(stream.offset(), 10);
+    let Some(Err(StreamError::Parse(failure))) = stream.next() else {
+        panic!("expected a parse error");
+    };
+    assert_eq!(failure.furthest.index(), 10);
+    assert_eq!(failure.expected_items(), vec!["ECHO ", "PING\n"]);
+}
+
+#[test]
+fn stream_length_prefixed() {
+    let parser = ParserWrapper::<u8, Vec<u8>, true>::from_parser(
+        binary_parsers::length_prefixed(binary_parsers::be_u8(), binary_parsers::take(2))
+            .map(<[u8]>::to_vec),
+    );
+
+    // the first block fills the first chunk exactly and is yielded without reading further
+    let pulled = Cell::new(0);
+    let chunks = [vec![2, 7, 8], vec![2, 9], vec![10]]
+        .into_iter()
+        .inspect(|_| pulled.set(pulled.get() + 1));
+    let mut stream = StreamParser::new(&parser, chunks);
+
+    assert_eq!(stream.next().unwrap().unwrap(), vec![7, 8]);
+    assert_eq!(pulled.get(), 1);
+    assert_eq!(stream.next().unwrap().unwrap(), vec![9, 10]);
+    assert!(stream.next().is_none());
+}
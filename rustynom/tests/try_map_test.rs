@@ -0,0 +1,95 @@
+use rustynom::{
+    ParsingRange,
+    atomic_parsers::LiteralParserNoOutput,
+    combinator_parsers::{SameOrParser2, Trailing},
+    parse_str,
+    parser::{ParserCombinator, ParserWrapper},
+    utility_parsers,
+};
+
+#[test]
+fn try_map() {
+    let octet = utility_parsers::uint()
+        .try_map(|n| u8::try_from(n).map_err(|_| format!("octet must be <= 255, found {}", n)));
+    let parser = ParserWrapper::<char, Vec<u8>, true>::from_parser(
+        octet
+            .separated_by(LiteralParserNoOutput::new('.'))
+            .trailing(Trailing::Forbid)
+            .then_eof(),
+    );
+
+    assert_eq!(
+        parse_str!(parser, "10.0.0.255").unwrap_success(),
+        vec![10, 0, 0, 255]
+    );
+
+    let failure = parse_str!(parser, "10.0.256.1").unwrap_failure();
+    assert_eq!(failure.message(), "octet must be <= 255, found 256");
+    assert_eq!(failure.furthest.index(), 5);
+    assert_eq!(failure.span(), Some(&ParsingRange::new(5.into(), 8.into())));
+}
+
+#[test]
+fn verify_and_filter() {
+    let even = utility_parsers::uint().verify(|n| n % 2 == 0, "expected an even number");
+    let parser = ParserWrapper::<char, u64, true>::from_parser(even);
+
+    assert_eq!(parse_str!(parser, "42").unwrap_success(), 42);
+    let failure = parse_str!(parser, "7").unwrap_failure();
+    assert_eq!(failure.message(), "expected an even number");
+    assert_eq!(failure.span(), Some(&ParsingRange::new(0.into(), 1.into())));
+
+    let parser = ParserWrapper::<char, u64, true>::from_parser(
+        utility_parsers::uint().filter(|n| *n > 0, "a positive number"),
+    );
+    assert_eq!(parse_str!(parser, "3").unwrap_success(), 3);
+    let failure = parse_str!(parser, "00").unwrap_failure();
+    assert_eq!(failure.furthest.index(), 0);
+    assert_eq!(failure.message(), "expected a positive number");
+    assert_eq!(failure.span(), Some(&ParsingRange::new(0.into(), 2.into())));
+
+    // the expected item is merged with those of other alternatives
+    let parser = ParserWrapper::<char, u64, true>::from_parser(SameOrParser2::new(
+        utility_parsers::uint().filter(|n| *n > 0, "a positive number"),
+        LiteralParserNoOutput::new('*').map(|_| 1),
+    ));
+    let failure = parse_str!(parser, "0").unwrap_failure();
+    assert_eq!(failure.expected_items(), vec!["*", "a positive number"]);
+}
+
+#[test]
+fn verify_in_alternative() {
+    let octet = || utility_parsers::uint().verify(|n| *n < 256, "octet too big");
+    let wildcard = || LiteralParserNoOutput::new('*').map(|_| 0);
+
+    for parser in [
+        ParserWrapper::<char, u64, true>::from_parser(SameOrParser2::new(wildcard(), octet())),
+        ParserWrapper::<char, u64, true>::from_parser(SameOrParser2::new(octet(), wildcard())),
+    ] {
+        assert_eq!(parse_str!(parser, "*").unwrap_success(), 0);
+        let failure = parse_str!(parser, "300").unwrap_failure();
+        assert_eq!(failure.message(), "octet too big");
+        assert_eq!(failure.span(), Some(&ParsingRange::new(0.into(), 3.into())));
+    }
+}
+
+#[test]
+fn and_then() {
+    // a count followed by that many letters
+    let letter = utility_parsers::test(|c: &char| c.is_alphabetic(), "a letter".to_string());
+    let parser = ParserWrapper::<char, Vec<char>, true>::from_parser(
+        utility_parsers::uint()
+            .skip(LiteralParserNoOutput::new(':'))
+            .and_then(move |n| letter.clone().repeat(n as usize))
+            .then_eof(),
+    );
+
+    assert_eq!(
+        parse_str!(parser, "3:abc").unwrap_success(),
+        vec!['a', 'b', 'c']
+    );
+    assert_eq!(parse_str!(parser, "0:").unwrap_success(), vec![]);
+
+    let failure = parse_str!(parser, "3:ab").unwrap_failure();
+    assert_eq!(failure.message(), "expected one of: 3 items, a letter");
+}
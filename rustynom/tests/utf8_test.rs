@@ -0,0 +1,75 @@
+use rustynom::{
+    atomic_parsers::{LiteralListParser, LiteralParserNoOutput},
+    combinator_parsers::AndParser4,
+    location::{LineColumn, LineIndex},
+    parsable::Utf8,
+    parser::{ParserCombinator, ParserWrapper},
+    utility_parsers::{self, UIntParser, WhiteSpaceParser},
+};
+
+#[test]
+fn simple_utf8() {
+    let parser = ParserWrapper::<Utf8, _, true>::from_parser(
+        AndParser4::new(
+            utility_parsers::multi_char_test(|c| c.is_alphabetic(), "a word".to_string()),
+            WhiteSpaceParser::<false, _>::new(),
+            UIntParser::new(),
+            LiteralParserNoOutput::new(b'!'),
+        )
+        .then_eof(),
+    );
+
+    let result = parser.parse_slice("héllo 123!".as_bytes());
+    assert_eq!(result.unwrap_success(), ("héllo".to_string(), (), 123, ()));
+
+    // positions are byte offsets, 'é' takes two bytes
+    let result = parser.parse_slice("héllo x".as_bytes());
+    let failure = result.unwrap_failure();
+    assert_eq!(failure.furthest.index(), 7);
+    assert_eq!(failure.expected, Some(vec!["a digit".to_string()]));
+}
+
+#[test]
+fn utf8_literal() {
+    let parser = ParserWrapper::<Utf8, _, true>::from_parser(
+        LiteralListParser::new("grüße".to_string()).separated_by(LiteralParserNoOutput::new(b',')),
+    );
+
+    let result = parser.parse_slice("grüße,grüße".as_bytes());
+    assert_eq!(
+        result.unwrap_success(),
+        vec!["grüße".to_string(), "grüße".to_string()]
+    );
+
+    let result = parser.parse_slice("grüsse".as_bytes());
+    let failure = result.unwrap_failure();
+    assert_eq!(failure.furthest.index(), 0);
+    assert_eq!(failure.expected, Some(vec!["grüße".to_string()]));
+}
+
+#[test]
+fn utf8_char_test() {
+    let parser = ParserWrapper::<Utf8, _, true>::from_parser(
+        utility_parsers::char_test(|c| c == 'λ', "lambda".to_string())
+            .many_non_empty()
+            .then_eof(),
+    );
+
+    let result = parser.parse_slice("λλλ".as_bytes());
+    assert_eq!(result.unwrap_success(), vec!['λ', 'λ', 'λ']);
+
+    let result = parser.parse_slice("λx".as_bytes());
+    let failure = result.unwrap_failure();
+    assert_eq!(failure.furthest.index(), 2);
+}
+
+#[test]
+fn utf8_line_index() {
+    let input = "ä\nb".as_bytes();
+    let index = LineIndex::new::<Utf8>(input);
+
+    assert_eq!(index.line_count(), 2);
+    // columns count characters, the line break follows the one character "ä"
+    assert_eq!(index.line_column(&2.into()), LineColumn::new(1, 2));
+    assert_eq!(index.line_column(&3.into()), LineColumn::new(2, 1));
+}
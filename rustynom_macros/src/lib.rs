@@ -23,7 +23,7 @@ pub fn and_parser(input: TokenStream) -> TokenStream {
 
     let where_clause: String = "TIn: Parsable, ".to_string()
         + (1..=n)
-            .map(|i| format!("T{}Parser: RawTestParser<TIn, ERROR>", i))
+            .map(|i| format!("T{}Parser: RawTestParser<'a, TIn, ERROR>", i))
             .collect::<Vec<String>>()
             .join(", ")
             .as_str();
@@ -34,8 +34,8 @@ pub fn and_parser(input: TokenStream) -> TokenStream {
     output.push_str("#[derive(Clone)]\n");
     output.push_str(
         format!(
-            "pub struct AndParser{}<{}> where {} {{",
-            n, type_args_decl, where_clause
+            "pub struct AndParser{}<{}> where TIn: Parsable {{",
+            n, type_args_decl
         )
         .as_str(),
     );
@@ -52,8 +52,8 @@ pub fn and_parser(input: TokenStream) -> TokenStream {
     // construct the impl block for AndParser
     output.push_str(
         format!(
-            "impl<{}> AndParser{}<{}> where {} {{",
-            type_args_decl, n, full_type_args, where_clause
+            "impl<{}> AndParser{}<{}> where TIn: Parsable {{",
+            type_args_decl, n, full_type_args
         )
         .as_str(),
     );
@@ -83,7 +83,7 @@ pub fn and_parser(input: TokenStream) -> TokenStream {
     // construct the impl block for Parser
     output.push_str(
         format!(
-            "impl<{}> RawTestParser<TIn, ERROR> for AndParser{}<{}> where {} {{",
+            "impl<'a, {}> RawTestParser<'a, TIn, ERROR> for AndParser{}<{}> where {} {{",
             type_args_decl, n, full_type_args, where_clause
         )
         .as_str(),
@@ -91,29 +91,23 @@ pub fn and_parser(input: TokenStream) -> TokenStream {
     let out_type = format!(
         "({})",
         (1..=n)
-            .map(|i| format!("<T{}Parser as RawTestParser<TIn, ERROR>>::TOut", i))
+            .map(|i| format!("<T{}Parser as RawTestParser<'a, TIn, ERROR>>::TOut", i))
             .collect::<Vec<String>>()
             .join(", ")
     );
 
-    output.push_str(
-        format!(
-            "type TOut = {};",
-            out_type
-        )
-        .as_str(),
-    );
+    output.push_str(format!("type TOut = {};", out_type).as_str());
 
     output.push_str(
         format!(
-            "fn parse(&self, input: &[TIn::T], position: &mut ParsingPosition) -> ParseResult<{}> {{",
+            "fn parse(&self, input: &'a [TIn::T], position: &mut ParsingPosition, context: &mut ParseContext<'a>) -> ParseResult<{}> {{",
             out_type
         )
         .as_str(),
     );
 
     for i in 1..=n {
-        output.push_str(format!("let r{} = self.p{}.parse(input, position);", i, i).as_str());
+        output.push_str(format!("let r{} = self.p{}.parse(input, position, context);", i, i).as_str());
         output.push_str(format!("if let ParseResult::Failure(x) = r{} {{", i).as_str());
         output.push_str("return ParseResult::Failure(x);");
         output.push_str("}");
@@ -143,11 +137,11 @@ pub fn or_parser(input: TokenStream) -> TokenStream {
     let n = input.parse::<usize>().unwrap();
 
     let full_type_args: String = "ERROR, TIn, ".to_string()
-    + (1..=n)
-        .map(|i| format!("T{}Parser", i))
-        .collect::<Vec<String>>()
-        .join(", ")
-        .as_str();
+        + (1..=n)
+            .map(|i| format!("T{}Parser", i))
+            .collect::<Vec<String>>()
+            .join(", ")
+            .as_str();
 
     let type_args_decl = "const ERROR: bool, TIn, ".to_string()
         + (1..=n)
@@ -163,7 +157,7 @@ pub fn or_parser(input: TokenStream) -> TokenStream {
 
     let where_clause: String = "TIn: Parsable, ".to_string()
         + (1..=n)
-            .map(|i| format!("T{}Parser: RawTestParser<TIn, ERROR>", i))
+            .map(|i| format!("T{}Parser: RawTestParser<'a, TIn, ERROR>", i))
             .collect::<Vec<String>>()
             .join(", ")
             .as_str();
@@ -186,8 +180,8 @@ pub fn or_parser(input: TokenStream) -> TokenStream {
     output.push_str("#[derive(Clone)]\n");
     output.push_str(
         format!(
-            "pub struct OrParser{}<{}> where {} {{",
-            n, type_args_decl, where_clause
+            "pub struct OrParser{}<{}> where TIn: Parsable {{",
+            n, type_args_decl
         )
         .as_str(),
     );
@@ -204,8 +198,8 @@ pub fn or_parser(input: TokenStream) -> TokenStream {
     // construct the impl block for OrParser
     output.push_str(
         format!(
-            "impl<{}> OrParser{}<{}> where {} {{",
-            type_args_decl, n, full_type_args, where_clause
+            "impl<{}> OrParser{}<{}> where TIn: Parsable {{",
+            type_args_decl, n, full_type_args
         )
         .as_str(),
     );
@@ -235,7 +229,7 @@ pub fn or_parser(input: TokenStream) -> TokenStream {
     // construct the impl block for Parser
     output.push_str(
         format!(
-            "impl<{}> RawTestParser<TIn, ERROR> for OrParser{}<{}> where {} {{",
+            "impl<'a, {}> RawTestParser<'a, TIn, ERROR> for OrParser{}<{}> where {} {{",
             type_args_decl, n, full_type_args, where_clause
         )
         .as_str(),
@@ -244,41 +238,42 @@ pub fn or_parser(input: TokenStream) -> TokenStream {
         "Variant{}<{}>",
         n,
         (1..=n)
-            .map(|i| format!("<T{}Parser as RawTestParser<TIn, ERROR>>::TOut", i))
+            .map(|i| format!("<T{}Parser as RawTestParser<'a, TIn, ERROR>>::TOut", i))
             .collect::<Vec<String>>()
             .join(", ")
     );
 
-    output.push_str(
-        format!(
-            "type TOut = {};",
-            out_type
-        )
-        .as_str(),
-    );
+    output.push_str(format!("type TOut = {};", out_type).as_str());
 
     output.push_str(
         format!(
-            "fn parse(&self, input: &[TIn::T], position: &mut ParsingPosition) -> ParseResult<{}> {{",
+            "fn parse(&self, input: &'a [TIn::T], position: &mut ParsingPosition, context: &mut ParseContext<'a>) -> ParseResult<{}> {{",
             out_type
         )
         .as_str(),
     );
 
     output.push_str("let initial_pos = position.clone();");
+    output.push_str("let initial_diagnostics = context.diagnostics_len();");
 
     for i in 1..n {
-        output.push_str(format!("let r{} = self.p{}.parse(input, position);", i, i).as_str());
+        output.push_str(format!("let r{} = self.p{}.parse(input, position, context);", i, i).as_str());
         output.push_str(format!("if let ParseResult::Success(x) = r{} {{", i).as_str());
         output.push_str(format!("return ParseResult::Success(Variant{}::V{}(x));", n, i).as_str());
+        // a committed failure must not be backtracked out of
+        output.push_str(format!("}} else if r{}.is_committed() {{", i).as_str());
+        output.push_str(format!("return ParseResult::Failure(r{}.unwrap_failure());", i).as_str());
         output.push_str("} else {");
         output.push_str("position.advance_to(initial_pos.clone());");
+        output.push_str("context.truncate_diagnostics(initial_diagnostics);");
         output.push_str("}");
     }
 
-    output.push_str(format!("let r{} = self.p{}.parse(input, position);", n, n).as_str());
+    output.push_str(format!("let r{} = self.p{}.parse(input, position, context);", n, n).as_str());
     output.push_str(format!("if let ParseResult::Success(x) = r{} {{", n).as_str());
     output.push_str(format!("return ParseResult::Success(Variant{}::V{}(x));", n, n).as_str());
+    output.push_str(format!("}} else if r{}.is_committed() {{", n).as_str());
+    output.push_str(format!("return ParseResult::Failure(r{}.unwrap_failure());", n).as_str());
     output.push('}');
 
     output.push_str("let mut failure = r1.unwrap_failure();");
@@ -300,11 +295,11 @@ pub fn or_parser(input: TokenStream) -> TokenStream {
     // Same Type
 
     let full_type_args: String = "ERROR, TIn, ".to_string()
-    + (1..=n)
-        .map(|i| format!("T{}Parser", i))
-        .collect::<Vec<String>>()
-        .join(", ")
-        .as_str();
+        + (1..=n)
+            .map(|i| format!("T{}Parser", i))
+            .collect::<Vec<String>>()
+            .join(", ")
+            .as_str();
 
     let type_args_decl = "const ERROR: bool, TIn, ".to_string()
         + (1..=n)
@@ -313,9 +308,9 @@ pub fn or_parser(input: TokenStream) -> TokenStream {
             .join(", ")
             .as_str();
 
-    let where_clause: String = "TIn: Parsable, T1Parser: RawTestParser<TIn, ERROR>, ".to_string()
+    let where_clause: String = "TIn: Parsable, T1Parser: RawTestParser<'a, TIn, ERROR>, ".to_string()
         + (2..=n)
-            .map(|i| format!("T{}Parser: RawTestParser<TIn, ERROR, TOut = <T1Parser as RawTestParser<TIn, ERROR>>::TOut>", i))
+            .map(|i| format!("T{}Parser: RawTestParser<'a, TIn, ERROR, TOut = <T1Parser as RawTestParser<'a, TIn, ERROR>>::TOut>", i))
             .collect::<Vec<String>>()
             .join(", ")
             .as_str();
@@ -324,8 +319,8 @@ pub fn or_parser(input: TokenStream) -> TokenStream {
     output.push_str("#[derive(Clone)]\n");
     output.push_str(
         format!(
-            "pub struct SameOrParser{}<{}> where {} {{",
-            n, type_args_decl, where_clause
+            "pub struct SameOrParser{}<{}> where TIn: Parsable {{",
+            n, type_args_decl
         )
         .as_str(),
     );
@@ -342,8 +337,8 @@ pub fn or_parser(input: TokenStream) -> TokenStream {
     // construct the impl block for OrParser
     output.push_str(
         format!(
-            "impl<{}> SameOrParser{}<{}> where {} {{",
-            type_args_decl, n, full_type_args, where_clause
+            "impl<{}> SameOrParser{}<{}> where TIn: Parsable {{",
+            type_args_decl, n, full_type_args
         )
         .as_str(),
     );
@@ -373,29 +368,34 @@ pub fn or_parser(input: TokenStream) -> TokenStream {
     // construct the impl block for Parser
     output.push_str(
         format!(
-            "impl<{}> RawTestParser<TIn, ERROR> for SameOrParser{}<{}> where {} {{",
+            "impl<'a, {}> RawTestParser<'a, TIn, ERROR> for SameOrParser{}<{}> where {} {{",
             type_args_decl, n, full_type_args, where_clause
         )
         .as_str(),
     );
 
-    let out_type = "<T1Parser as RawTestParser<TIn, ERROR>>::TOut";
+    let out_type = "<T1Parser as RawTestParser<'a, TIn, ERROR>>::TOut";
     output.push_str(format!("type TOut = {};", out_type).as_str());
 
-    output.push_str(format!("fn parse(&self, input: &[TIn::T], position: &mut ParsingPosition) -> ParseResult<{}> {{", out_type).as_str());
+    output.push_str(format!("fn parse(&self, input: &'a [TIn::T], position: &mut ParsingPosition, context: &mut ParseContext<'a>) -> ParseResult<{}> {{", out_type).as_str());
     output.push_str("let initial_pos = position.clone();");
+    output.push_str("let initial_diagnostics = context.diagnostics_len();");
 
     for i in 1..n {
-        output.push_str(format!("let r{} = self.p{}.parse(input, position);", i, i).as_str());
+        output.push_str(format!("let r{} = self.p{}.parse(input, position, context);", i, i).as_str());
         output.push_str(format!("if r{}.is_success() {{", i).as_str());
         output.push_str(format!("return r{};", i).as_str());
+        // a committed failure must not be backtracked out of
+        output.push_str(format!("}} else if r{}.is_committed() {{", i).as_str());
+        output.push_str(format!("return r{};", i).as_str());
         output.push_str("} else {");
         output.push_str("position.advance_to(initial_pos.clone());");
+        output.push_str("context.truncate_diagnostics(initial_diagnostics);");
         output.push_str("}");
     }
 
-    output.push_str(format!("let r{} = self.p{}.parse(input, position);", n, n).as_str());
-    output.push_str(format!("if r{}.is_success() {{", n).as_str());
+    output.push_str(format!("let r{} = self.p{}.parse(input, position, context);", n, n).as_str());
+    output.push_str(format!("if r{}.is_success() || r{}.is_committed() {{", n, n).as_str());
     output.push_str(format!("return r{};", n).as_str());
     output.push('}');
 